@@ -0,0 +1,100 @@
+// Compares the bitset matching in day 4 against checking every number with `Vec::contains`.
+// Run with `cargo bench --bench day4`.
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use advent_of_code_2023::day4;
+use advent_of_code_2023::random::Rng;
+
+const CARDS: usize = 20_000;
+const WINNING: usize = 40;
+const NUMS: usize = 80;
+const RUNS: u32 = 10;
+
+// `count` distinct numbers from `1..max`
+fn distinct_nums(rng: &mut Rng, count: usize, max: usize) -> Vec<usize> {
+    let mut pool = (1..max).collect::<Vec<_>>();
+
+    for i in 0..count {
+        let j = rng.between(i, pool.len() - 1);
+        pool.swap(i, j);
+    }
+
+    pool.truncate(count);
+    pool
+}
+
+fn synthetic_cards(rng: &mut Rng, max: usize) -> Vec<(Vec<usize>, Vec<usize>)> {
+    (0..CARDS)
+        .map(|_| {
+            (
+                distinct_nums(rng, WINNING, max),
+                distinct_nums(rng, NUMS, max),
+            )
+        })
+        .collect()
+}
+
+fn to_input(cards: &[(Vec<usize>, Vec<usize>)]) -> String {
+    let join = |nums: &[usize]| {
+        nums.iter()
+            .map(|num| format!("{:>2}", num))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    cards
+        .iter()
+        .enumerate()
+        .map(|(i, (winning, nums))| format!("Card {}: {} | {}", i + 1, join(winning), join(nums)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn naive_part1(cards: &[(Vec<usize>, Vec<usize>)]) -> usize {
+    cards
+        .iter()
+        .map(|(winning, nums)| {
+            let matches = nums.iter().filter(|num| winning.contains(num)).count();
+            match matches {
+                0 => 0,
+                matches => 1 << (matches - 1),
+            }
+        })
+        .sum()
+}
+
+fn time<F: FnMut() -> usize>(mut f: F) -> (usize, Duration) {
+    let result = f();
+    let start = Instant::now();
+
+    for _ in 0..RUNS {
+        black_box(f());
+    }
+
+    (result, start.elapsed() / RUNS)
+}
+
+fn bench(name: &str, max: usize) {
+    let mut rng = Rng::new(4);
+    let raw = synthetic_cards(&mut rng, max);
+    let cards = day4::input_generator(&to_input(&raw));
+
+    let (naive, naive_time) = time(|| naive_part1(black_box(&raw)));
+    let (bitset, bitset_time) = time(|| day4::solve_part1(black_box(&cards)));
+
+    assert_eq!(naive, bitset);
+
+    println!(
+        "{}: Vec::contains {:?}, bitset {:?} ({:.1}x)",
+        name,
+        naive_time,
+        bitset_time,
+        naive_time.as_secs_f64() / bitset_time.as_secs_f64()
+    );
+}
+
+fn main() {
+    bench("numbers below 100", 100);
+    bench("numbers below 10000", 10_000);
+}
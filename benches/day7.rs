@@ -0,0 +1,60 @@
+// Compares sorting day 7 hands by packed keys against sorting with `Rules::compare_hands`.
+// Run with `cargo bench --bench day7`.
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use advent_of_code_2023::day7::{self, Rules};
+use advent_of_code_2023::random::Rng;
+
+const HANDS: usize = 1_000_000;
+const RUNS: u32 = 3;
+const CARDS: [char; 13] = [
+    '2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A',
+];
+
+fn synthetic_input(rng: &mut Rng) -> String {
+    (0..HANDS)
+        .map(|_| {
+            let cards = (0..5).map(|_| CARDS[rng.below(13)]).collect::<String>();
+            format!("{} {}", cards, rng.between(1, 1000))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn time<F: FnMut() -> Vec<usize>>(mut f: F) -> (Vec<usize>, Duration) {
+    let result = f();
+    let start = Instant::now();
+
+    for _ in 0..RUNS {
+        black_box(f());
+    }
+
+    (result, start.elapsed() / RUNS)
+}
+
+fn bench(name: &str, rules: &Rules) {
+    let mut rng = Rng::new(7);
+    let hands = day7::input_generator(&synthetic_input(&mut rng));
+
+    let bids = |ranked: Vec<&day7::Hand>| ranked.iter().map(|hand| hand.bid()).collect();
+
+    let (compared, compared_time) =
+        time(|| bids(day7::rank_hands_by_comparison(black_box(&hands), rules)));
+    let (keyed, keyed_time) = time(|| bids(day7::rank_hands(black_box(&hands), rules)));
+
+    assert_eq!(compared, keyed);
+
+    println!(
+        "{}: compare_hands {:?}, sort keys {:?} ({:.1}x)",
+        name,
+        compared_time,
+        keyed_time,
+        compared_time.as_secs_f64() / keyed_time.as_secs_f64()
+    );
+}
+
+fn main() {
+    bench("standard rules", &Rules::standard());
+    bench("joker rules", &Rules::jokers());
+}
@@ -19,7 +19,7 @@ pub fn input_generator(input: &str) -> Vec<Vec<u32>> {
                     if c.is_numeric() {
                         return c.to_digit(BASE_10);
                     }
-                    return None;
+                    None
                 })
                 .collect()
         })
@@ -29,7 +29,7 @@ pub fn input_generator(input: &str) -> Vec<Vec<u32>> {
 #[aoc(day1, part1)]
 pub fn solve_part1(input: &[Vec<u32>]) -> u32 {
     input
-        .into_iter()
+        .iter()
         .map(|l| {
             let len = l.len();
             l[0] * 10 + l[len - 1]
@@ -40,7 +40,7 @@ pub fn solve_part1(input: &[Vec<u32>]) -> u32 {
 #[aoc(day1, part2)]
 pub fn solve_part2(input: &[Vec<u32>]) -> u32 {
     input
-        .into_iter()
+        .iter()
         .map(|l| {
             let len = l.len();
             l[0] * 10 + l[len - 1]
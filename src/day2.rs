@@ -3,8 +3,10 @@ use nom::bytes::complete::tag;
 use nom::character::complete::char;
 use nom::multi::separated_list1;
 use nom::{character::complete::digit1, sequence::separated_pair, IResult};
+use std::fmt;
 
 use crate::parsers::parse_usize;
+use crate::random::Rng;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 enum Color {
@@ -14,7 +16,7 @@ enum Color {
 }
 
 impl Color {
-    fn value(self: &Self) -> usize {
+    fn value(&self) -> usize {
         match self {
             Color::Red(val) => *val,
             Color::Green(val) => *val,
@@ -45,7 +47,7 @@ struct Round {
 }
 
 impl Round {
-    fn is_valid(self: &Self, cube_limits: (usize, usize, usize)) -> bool {
+    fn is_valid(&self, cube_limits: (usize, usize, usize)) -> bool {
         let (r, g, b) = cube_limits;
 
         self.red.value() <= r && self.green.value() <= g && self.blue.value() <= b
@@ -58,11 +60,11 @@ pub struct Game {
 }
 
 impl Game {
-    fn is_valid(self: &Self, cube_limits: (usize, usize, usize)) -> bool {
+    fn is_valid(&self, cube_limits: (usize, usize, usize)) -> bool {
         self.rounds.iter().all(|round| round.is_valid(cube_limits))
     }
 
-    fn min_set(self: &Self) -> (usize, usize, usize) {
+    fn min_set(&self) -> (usize, usize, usize) {
         let mut r = 0;
         let mut g = 0;
         let mut b = 0;
@@ -95,6 +97,206 @@ fn min_set_product(input: (usize, usize, usize)) -> usize {
     a * b * c
 }
 
+impl fmt::Display for Round {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let colors = [
+            (self.red.value(), "red"),
+            (self.green.value(), "green"),
+            (self.blue.value(), "blue"),
+        ];
+
+        let shown = colors
+            .iter()
+            .filter(|(value, _)| *value > 0)
+            .map(|(value, name)| format!("{} {}", value, name))
+            .collect::<Vec<_>>();
+
+        write!(f, "{}", shown.join(", "))
+    }
+}
+
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rounds = self
+            .rounds
+            .iter()
+            .map(|round| round.to_string())
+            .collect::<Vec<_>>();
+
+        write!(f, "Game {}: {}", self.id, rounds.join("; "))
+    }
+}
+
+// The elf grabs a handful of between 1 and all of the cubes, every handful size being equally
+// likely, shows them and puts them back in the bag
+fn simulate_round(bag: (usize, usize, usize), rng: &mut Rng) -> Round {
+    let (r, g, b) = bag;
+    let mut remaining = [r, g, b];
+    let mut drawn = [0; 3];
+
+    let handful = rng.between(1, r + g + b);
+
+    for _ in 0..handful {
+        let mut pick = rng.below(remaining.iter().sum());
+
+        for (left, count) in remaining.iter_mut().zip(drawn.iter_mut()) {
+            if pick < *left {
+                *left -= 1;
+                *count += 1;
+                break;
+            }
+
+            pick -= *left;
+        }
+    }
+
+    Round {
+        red: Color::Red(drawn[0]),
+        green: Color::Green(drawn[1]),
+        blue: Color::Blue(drawn[2]),
+    }
+}
+
+// A game needs at least one round for its log line to parse
+pub fn simulate_game(id: usize, bag: (usize, usize, usize), rounds: usize, rng: &mut Rng) -> Game {
+    let (r, g, b) = bag;
+    assert!(r + g + b > 0, "Bag must contain at least one cube");
+    assert!(rounds > 0, "Game must have at least one round");
+
+    Game {
+        id,
+        rounds: (0..rounds).map(|_| simulate_round(bag, rng)).collect(),
+    }
+}
+
+// Games are numbered from 1 and each has between 1 and `max_rounds` rounds
+pub fn simulate_games(
+    bag: (usize, usize, usize),
+    count: usize,
+    max_rounds: usize,
+    rng: &mut Rng,
+) -> Vec<Game> {
+    assert!(max_rounds > 0, "Games must have at least one round");
+
+    (1..=count)
+        .map(|id| {
+            let rounds = rng.between(1, max_rounds);
+            simulate_game(id, bag, rounds, rng)
+        })
+        .collect()
+}
+
+pub fn game_log(games: &[Game]) -> String {
+    games
+        .iter()
+        .map(|game| game.to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn ln_choose(n: usize, k: usize) -> f64 {
+    if k > n {
+        return f64::NEG_INFINITY;
+    }
+
+    (0..k)
+        .map(|i| ((n - i) as f64).ln() - ((i + 1) as f64).ln())
+        .sum()
+}
+
+// Likelihood of a round under the same model as `simulate_round`: a uniform handful size
+// followed by a multivariate hypergeometric draw of that many cubes
+fn round_log_likelihood(round: &Round, bag: (usize, usize, usize)) -> f64 {
+    let (r, g, b) = bag;
+    let (red, green, blue) = (round.red.value(), round.green.value(), round.blue.value());
+
+    let total = r + g + b;
+    let handful = red + green + blue;
+
+    if handful == 0 || handful > total {
+        return f64::NEG_INFINITY;
+    }
+
+    ln_choose(r, red) + ln_choose(g, green) + ln_choose(b, blue)
+        - ln_choose(total, handful)
+        - (total as f64).ln()
+}
+
+pub fn log_likelihood(games: &[Game], bag: (usize, usize, usize)) -> f64 {
+    games
+        .iter()
+        .flat_map(|game| game.rounds.iter())
+        .map(|round| round_log_likelihood(round, bag))
+        .sum()
+}
+
+// Smallest bag that could have produced every one of the games
+fn combined_min_set(games: &[Game]) -> (usize, usize, usize) {
+    games
+        .iter()
+        .map(|game| game.min_set())
+        .fold((0, 0, 0), |(r, g, b), (game_r, game_g, game_b)| {
+            (r.max(game_r), g.max(game_g), b.max(game_b))
+        })
+}
+
+// Every bag from the combined min set up to `max_cubes` of each colour
+pub fn candidate_bags(games: &[Game], max_cubes: usize) -> Vec<(usize, usize, usize)> {
+    let (min_r, min_g, min_b) = combined_min_set(games);
+
+    let mut candidates = vec![];
+
+    for r in min_r..=max_cubes {
+        for g in min_g..=max_cubes {
+            for b in min_b..=max_cubes {
+                candidates.push((r, g, b));
+            }
+        }
+    }
+
+    candidates
+}
+
+pub fn maximum_likelihood_bag(
+    games: &[Game],
+    candidates: &[(usize, usize, usize)],
+) -> Option<((usize, usize, usize), f64)> {
+    candidates
+        .iter()
+        .map(|&bag| (bag, log_likelihood(games, bag)))
+        .filter(|(_, likelihood)| likelihood.is_finite())
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+// Posterior probability of each candidate bag given a uniform prior over the candidates.
+// Candidates that cannot explain the games are left out.
+pub fn bag_posterior(
+    games: &[Game],
+    candidates: &[(usize, usize, usize)],
+) -> Vec<((usize, usize, usize), f64)> {
+    let likelihoods = candidates
+        .iter()
+        .map(|&bag| (bag, log_likelihood(games, bag)))
+        .filter(|(_, likelihood)| likelihood.is_finite())
+        .collect::<Vec<_>>();
+
+    let max = likelihoods
+        .iter()
+        .map(|(_, likelihood)| *likelihood)
+        .fold(f64::NEG_INFINITY, f64::max);
+
+    // Shift by the maximum before exponentiating so the weights don't underflow
+    let total: f64 = likelihoods
+        .iter()
+        .map(|(_, likelihood)| (likelihood - max).exp())
+        .sum();
+
+    likelihoods
+        .into_iter()
+        .map(|(bag, likelihood)| (bag, (likelihood - max).exp() / total))
+        .collect()
+}
+
 fn parse_color(input: &str) -> IResult<&str, Color> {
     let (input, (value_str, color_str)) = separated_pair(
         digit1,
@@ -112,30 +314,21 @@ fn parse_round(input: &str) -> IResult<&str, Round> {
 
     let red = result
         .iter()
-        .find(|&&c| match c {
-            Color::Red(_) => true,
-            _ => false,
-        })
-        .unwrap_or(&Color::Red(0))
-        .clone();
+        .find(|&&c| matches!(c, Color::Red(_)))
+        .copied()
+        .unwrap_or(Color::Red(0));
 
     let green = result
         .iter()
-        .find(|&&c| match c {
-            Color::Green(_) => true,
-            _ => false,
-        })
-        .unwrap_or(&Color::Green(0))
-        .clone();
+        .find(|&&c| matches!(c, Color::Green(_)))
+        .copied()
+        .unwrap_or(Color::Green(0));
 
     let blue = result
         .iter()
-        .find(|&&c| match c {
-            Color::Blue(_) => true,
-            _ => false,
-        })
-        .unwrap_or(&Color::Blue(0))
-        .clone();
+        .find(|&&c| matches!(c, Color::Blue(_)))
+        .copied()
+        .unwrap_or(Color::Blue(0));
 
     IResult::Ok((input, Round { red, green, blue }))
 }
@@ -167,7 +360,7 @@ fn parse_game(input: &str) -> IResult<&str, Game> {
 pub fn input_generator(input: &str) -> Vec<Game> {
     let result = separated_list1(char('\n'), parse_game)(input);
 
-    result.ok().expect("Bad parse").1
+    result.expect("Bad parse").1
 }
 
 #[aoc(day2, part1)]
@@ -178,7 +371,7 @@ pub fn solve_part1(input: &[Game]) -> usize {
             if game.is_valid((12, 13, 14)) {
                 return Some(game.id);
             }
-            return None;
+            None
         })
         .sum()
 }
@@ -261,6 +454,80 @@ mod tests {
         assert_eq!(result.2, 6);
     }
 
+    #[test]
+    fn simulated_games_round_trip_test() {
+        let mut rng = Rng::new(2023);
+        let games = simulate_games((12, 13, 14), 50, 6, &mut rng);
+
+        let log = game_log(&games);
+        let parsed = input_generator(&log);
+
+        assert_eq!(parsed.len(), 50);
+        for (game, parsed_game) in games.iter().zip(parsed.iter()) {
+            assert!(game.is_valid((12, 13, 14)));
+            assert_eq!(parsed_game.id, game.id);
+            assert_eq!(parsed_game.rounds.len(), game.rounds.len());
+            assert_eq!(parsed_game.min_set(), game.min_set());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Game must have at least one round")]
+    fn simulate_game_without_rounds_test() {
+        simulate_game(1, (12, 13, 14), 0, &mut Rng::new(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Games must have at least one round")]
+    fn simulate_games_without_rounds_test() {
+        simulate_games((12, 13, 14), 3, 0, &mut Rng::new(1));
+    }
+
+    #[test]
+    fn game_display_test() {
+        let input = "Game 3: 4 red, 3 green, 2 blue; 4 green, 3 blue";
+        let result = parse_game(input).ok().unwrap().1;
+
+        assert_eq!(result.to_string(), input);
+    }
+
+    #[test]
+    fn log_likelihood_impossible_bag_test() {
+        let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green";
+        let games = input_generator(input);
+
+        assert_eq!(log_likelihood(&games, (4, 2, 5)), f64::NEG_INFINITY);
+        assert!(log_likelihood(&games, (4, 2, 6)).is_finite());
+    }
+
+    #[test]
+    fn maximum_likelihood_bag_test() {
+        let mut rng = Rng::new(7);
+        let games = simulate_games((12, 13, 14), 200, 6, &mut rng);
+
+        let candidates = candidate_bags(&games, 20);
+        let (bag, _) = maximum_likelihood_bag(&games, &candidates).unwrap();
+
+        assert_eq!(bag, (12, 13, 14));
+    }
+
+    #[test]
+    fn bag_posterior_test() {
+        let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green";
+        let games = input_generator(input);
+
+        let candidates = candidate_bags(&games, 8);
+        let posterior = bag_posterior(&games, &candidates);
+
+        let total: f64 = posterior.iter().map(|(_, p)| p).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        assert_eq!(posterior.len(), candidates.len());
+
+        let (most_likely, _) = posterior.iter().max_by(|a, b| a.1.total_cmp(&b.1)).unwrap();
+        let (best, _) = maximum_likelihood_bag(&games, &candidates).unwrap();
+        assert_eq!(*most_likely, best);
+    }
+
     #[test]
     fn part1_test() {
         let input_str1: &str = r"
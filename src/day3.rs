@@ -0,0 +1,209 @@
+#[derive(Debug, PartialEq)]
+struct PartNumber {
+    value: usize,
+    row: usize,
+    // First and last column of the digits
+    start: usize,
+    end: usize,
+}
+
+impl PartNumber {
+    fn is_adjacent(&self, symbol: &Symbol) -> bool {
+        let rows = self.row.saturating_sub(1)..=(self.row + 1);
+        let cols = self.start.saturating_sub(1)..=(self.end + 1);
+
+        rows.contains(&symbol.row) && cols.contains(&symbol.col)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Symbol {
+    value: char,
+    row: usize,
+    col: usize,
+}
+
+pub struct Schematic {
+    numbers: Vec<PartNumber>,
+    symbols: Vec<Symbol>,
+}
+
+fn parse_line(row: usize, line: &str, schematic: &mut Schematic) {
+    let mut current: Option<PartNumber> = None;
+
+    for (col, c) in line.chars().enumerate() {
+        if let Some(digit) = c.to_digit(10) {
+            let number = current.get_or_insert(PartNumber {
+                value: 0,
+                row,
+                start: col,
+                end: col,
+            });
+            number.value = number.value * 10 + digit as usize;
+            number.end = col;
+            continue;
+        }
+
+        if let Some(number) = current.take() {
+            schematic.numbers.push(number);
+        }
+
+        if c != '.' {
+            schematic.symbols.push(Symbol { value: c, row, col });
+        }
+    }
+
+    if let Some(number) = current {
+        schematic.numbers.push(number);
+    }
+}
+
+#[aoc_generator(day3)]
+pub fn input_generator(input: &str) -> Schematic {
+    let mut schematic = Schematic {
+        numbers: vec![],
+        symbols: vec![],
+    };
+
+    for (row, line) in input.lines().enumerate() {
+        parse_line(row, line.trim(), &mut schematic);
+    }
+
+    schematic
+}
+
+#[aoc(day3, part1)]
+pub fn solve_part1(input: &Schematic) -> usize {
+    input
+        .numbers
+        .iter()
+        .filter(|number| input.symbols.iter().any(|s| number.is_adjacent(s)))
+        .map(|number| number.value)
+        .sum()
+}
+
+#[aoc(day3, part2)]
+pub fn solve_part2(input: &Schematic) -> usize {
+    input
+        .symbols
+        .iter()
+        .filter(|symbol| symbol.value == '*')
+        .filter_map(|symbol| {
+            let neighbours = input
+                .numbers
+                .iter()
+                .filter(|number| number.is_adjacent(symbol))
+                .collect::<Vec<_>>();
+
+            if neighbours.len() == 2 {
+                return Some(neighbours[0].value * neighbours[1].value);
+            }
+            None
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_line_test() {
+        let mut schematic = Schematic {
+            numbers: vec![],
+            symbols: vec![],
+        };
+
+        parse_line(2, "..35..633*", &mut schematic);
+
+        assert_eq!(
+            schematic.numbers,
+            vec![
+                PartNumber {
+                    value: 35,
+                    row: 2,
+                    start: 2,
+                    end: 3
+                },
+                PartNumber {
+                    value: 633,
+                    row: 2,
+                    start: 6,
+                    end: 8
+                }
+            ]
+        );
+        assert_eq!(
+            schematic.symbols,
+            vec![Symbol {
+                value: '*',
+                row: 2,
+                col: 9
+            }]
+        );
+    }
+
+    #[test]
+    fn is_adjacent_test() {
+        let number = PartNumber {
+            value: 467,
+            row: 0,
+            start: 0,
+            end: 2,
+        };
+
+        let diagonal = Symbol {
+            value: '*',
+            row: 1,
+            col: 3,
+        };
+        let too_far = Symbol {
+            value: '*',
+            row: 1,
+            col: 4,
+        };
+
+        assert!(number.is_adjacent(&diagonal));
+        assert!(!number.is_adjacent(&too_far));
+    }
+
+    #[test]
+    fn part1_test() {
+        let input_str1: &str = r"
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..
+"
+        .trim();
+
+        let input = input_generator(input_str1);
+        assert_eq!(solve_part1(&input), 4361);
+    }
+
+    #[test]
+    fn part2_test() {
+        let input_str2: &str = r"
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..
+"
+        .trim();
+
+        let input = input_generator(input_str2);
+        assert_eq!(solve_part2(&input), 467835);
+    }
+}
@@ -4,52 +4,364 @@ use nom::multi::many1;
 use nom::sequence::separated_pair;
 use nom::{multi::separated_list1, IResult};
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
 use crate::parsers::parse_usize;
 
+// Set of card numbers. Puzzle numbers are all below 100, so they live in a bitset and only
+// larger values fall back to a sorted list.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NumberSet {
+    bits: u128,
+    overflow: Vec<usize>,
+}
+
+impl NumberSet {
+    const BITS: usize = u128::BITS as usize;
+
+    fn insert(&mut self, num: usize) {
+        if num < Self::BITS {
+            self.bits |= 1 << num;
+            return;
+        }
+
+        if let Err(index) = self.overflow.binary_search(&num) {
+            self.overflow.insert(index, num);
+        }
+    }
+
+    fn contains(&self, num: usize) -> bool {
+        if num < Self::BITS {
+            return self.bits & (1 << num) != 0;
+        }
+
+        self.overflow.binary_search(&num).is_ok()
+    }
+
+    // Number of values in both sets, each value counting once
+    fn intersection_len(&self, other: &Self) -> usize {
+        let small = (self.bits & other.bits).count_ones() as usize;
+        let large = self
+            .overflow
+            .iter()
+            .filter(|num| other.contains(**num))
+            .count();
+
+        small + large
+    }
+}
+
+impl FromIterator<usize> for NumberSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = NumberSet::default();
+
+        for num in iter {
+            set.insert(num);
+        }
+
+        set
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Scoring {
+    // 1, 2, 4, 8, ...
+    Doubling,
+    // 1, 2, 3, 4, ...
+    Linear,
+    // 1, 2, 3, 5, 8, ...
+    Fibonacci,
+}
+
+impl Scoring {
+    fn score(self, matches: usize) -> usize {
+        if matches == 0 {
+            return 0;
+        }
+
+        match self {
+            Scoring::Doubling => 1 << (matches - 1),
+            Scoring::Linear => matches,
+            Scoring::Fibonacci => {
+                let (mut prev, mut score) = (1, 1);
+
+                for _ in 1..matches {
+                    (prev, score) = (score, prev + score);
+                }
+
+                score
+            }
+        }
+    }
+}
+
+// Variants of the scratchcard game. The default is the puzzle's rules.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rules {
+    pub scoring: Scoring,
+    // Distance from a card to the first card it wins, must be at least 1
+    pub offset: usize,
+    // Distance between consecutive won cards, so 2 wins every other card
+    pub stride: usize,
+    // Most copies, including the original, that any single card can end up with
+    pub copy_cap: Option<usize>,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            scoring: Scoring::Doubling,
+            offset: 1,
+            stride: 1,
+            copy_cap: None,
+        }
+    }
+}
+
+impl Rules {
+    // Every card has to win copies of later cards, otherwise it would keep winning itself
+    pub fn validate(&self) -> Result<(), CardError> {
+        if self.offset == 0 {
+            return Err(CardError::ZeroOffset);
+        }
+
+        Ok(())
+    }
+
+    fn won_card_ids(&self, id: usize, matches: usize) -> impl Iterator<Item = usize> {
+        let Rules { offset, stride, .. } = *self;
+
+        (0..matches).map(move |i| id + offset + i * stride)
+    }
+
+    fn cap_copies(&self, copies: usize) -> usize {
+        match self.copy_cap {
+            Some(cap) => copies.min(cap),
+            None => copies,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Card {
     id: usize,
-    winning: Vec<usize>,
-    nums: Vec<usize>,
+    winning: NumberSet,
+    nums: NumberSet,
 }
 
 impl Card {
-    fn get_score(self: &Self) -> usize {
-        let mut score = 0;
-        self.nums.iter().for_each(|num| {
-            if self.winning.contains(num) {
-                if score == 0 {
-                    score = 1;
-                } else {
-                    score *= 2
-                }
+    fn get_score(&self, rules: &Rules) -> usize {
+        rules.scoring.score(self.get_part2_score())
+    }
+
+    fn get_part2_score(&self) -> usize {
+        self.winning.intersection_len(&self.nums)
+    }
+}
+
+// Single forward pass over the cards for part 2. Only the copies won for cards that haven't been
+// reached yet are kept, in a ring buffer whose front is the next card ID. The buffer grows to the
+// reach of the largest match count seen so far, so memory doesn't depend on the number of cards.
+pub struct CopyPropagator {
+    rules: Rules,
+    next_id: usize,
+    pending: VecDeque<usize>,
+}
+
+impl CopyPropagator {
+    pub fn new(rules: Rules) -> Result<Self, CardError> {
+        rules.validate()?;
+
+        Ok(CopyPropagator {
+            rules,
+            next_id: 0,
+            pending: VecDeque::new(),
+        })
+    }
+
+    // Cards must arrive in ascending ID order. Returns the number of copies of the card,
+    // including the original.
+    pub fn push(&mut self, card: &Card) -> Result<usize, CardError> {
+        if card.id < self.next_id {
+            return Err(CardError::OutOfOrder(card.id));
+        }
+
+        // Copies won for IDs that were skipped are dropped, those cards were never dealt
+        let skipped = card.id - self.next_id;
+        self.pending.drain(..skipped.min(self.pending.len()));
+
+        let won = self.pending.pop_front().unwrap_or(0);
+        let copies = self.rules.cap_copies(1 + won);
+        self.next_id = card.id + 1;
+
+        for won_id in self.rules.won_card_ids(card.id, card.get_part2_score()) {
+            let slot = won_id - self.next_id;
+
+            if slot >= self.pending.len() {
+                self.pending.resize(slot + 1, 0);
             }
-        });
 
-        score
+            self.pending[slot] += copies;
+        }
+
+        Ok(copies)
     }
+}
 
-    fn get_part2_score(self: &Self) -> usize {
-        self.nums
-            .iter()
-            .filter(|num| self.winning.contains(num))
-            .collect::<Vec<_>>()
-            .len()
+#[derive(Debug, PartialEq)]
+pub enum CardError {
+    DuplicateId(usize),
+    // A card pushed after a card with the same or a higher ID
+    OutOfOrder(usize),
+    // Rules with an offset of 0, see `Rules::validate`
+    ZeroOffset,
+}
+
+// Cards sorted by ID, along with any IDs missing between the lowest and highest card
+pub struct CardTable {
+    cards: Vec<Card>,
+    missing: Vec<usize>,
+}
+
+impl CardTable {
+    pub fn new(input: &[Card]) -> Result<Self, CardError> {
+        let mut cards = input.to_vec();
+        cards.sort_by_key(|card| card.id);
+
+        let mut missing = vec![];
+
+        for pair in cards.windows(2) {
+            let (prev, next) = (pair[0].id, pair[1].id);
+
+            if prev == next {
+                return Err(CardError::DuplicateId(prev));
+            }
+
+            missing.extend((prev + 1)..next);
+        }
+
+        Ok(CardTable { cards, missing })
     }
 
-    fn process(self: &Self, score_map: &mut HashMap<usize, usize>) -> usize {
-        let score = self.get_part2_score();
-        let won_card_ids = ((self.id + 1)..(self.id + 1 + score)).collect::<Vec<_>>();
-        let won_cards_score: usize = won_card_ids
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    pub fn missing(&self) -> &[usize] {
+        &self.missing
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CascadeEntry {
+    pub id: usize,
+    pub matches: usize,
+    pub copies: usize,
+    // Earlier cards that won copies of this one and how many copies each of them produced
+    pub sources: Vec<(usize, usize)>,
+}
+
+// Breakdown of how part 2 arrives at its total, one entry per card in ID order
+pub struct Cascade {
+    entries: Vec<CascadeEntry>,
+}
+
+impl Cascade {
+    pub fn new(table: &CardTable, rules: &Rules) -> Result<Self, CardError> {
+        rules.validate()?;
+
+        let input = table.cards();
+
+        let index_of = input
+            .iter()
+            .enumerate()
+            .map(|(index, card)| (card.id, index))
+            .collect::<HashMap<_, _>>();
+
+        let mut entries = input
             .iter()
-            .map(|card_id| score_map.get(card_id).unwrap_or(&1).to_owned())
-            .sum();
+            .map(|card| CascadeEntry {
+                id: card.id,
+                matches: card.get_part2_score(),
+                copies: 1,
+                sources: vec![],
+            })
+            .collect::<Vec<_>>();
+
+        for index in 0..entries.len() {
+            let (id, matches, copies) = {
+                let entry = &mut entries[index];
+                entry.copies = rules.cap_copies(entry.copies);
+                (entry.id, entry.matches, entry.copies)
+            };
+
+            for won_id in rules.won_card_ids(id, matches) {
+                if let Some(&won_index) = index_of.get(&won_id) {
+                    let won = &mut entries[won_index];
+                    won.copies += copies;
+                    won.sources.push((id, copies));
+                }
+            }
+        }
+
+        Ok(Cascade { entries })
+    }
+
+    pub fn entries(&self) -> &[CascadeEntry] {
+        &self.entries
+    }
+
+    pub fn total(&self) -> usize {
+        self.entries.iter().map(|entry| entry.copies).sum()
+    }
+
+    pub fn to_table(&self) -> String {
+        let mut lines = vec!["Card | Matches | Copies | Won from".to_string()];
+
+        for entry in self.entries.iter() {
+            let sources = entry
+                .sources
+                .iter()
+                .map(|(id, copies)| format!("{} (+{})", id, copies))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            let line = format!(
+                "{:>4} | {:>7} | {:>6} | {}",
+                entry.id, entry.matches, entry.copies, sources
+            );
+            lines.push(line.trim_end().to_string());
+        }
+
+        lines.join("\n")
+    }
+
+    pub fn to_tree(&self) -> String {
+        let mut lines = vec![];
+
+        for entry in self.entries.iter() {
+            let plural = if entry.copies == 1 { "copy" } else { "copies" };
+            lines.push(format!(
+                "Card {}: {} {} ({} matches)",
+                entry.id, entry.copies, plural, entry.matches
+            ));
+
+            let mut branches = vec!["original (+1)".to_string()];
+            branches.extend(
+                entry
+                    .sources
+                    .iter()
+                    .map(|(id, copies)| format!("won by card {} (+{})", id, copies)),
+            );
+
+            let last = branches.len() - 1;
+            for (i, branch) in branches.iter().enumerate() {
+                let prefix = if i == last { "└──" } else { "├──" };
+                lines.push(format!("{} {}", prefix, branch));
+            }
+        }
 
-        let score = won_cards_score + 1;
-        score_map.insert(self.id, score);
-        score
+        lines.join("\n")
     }
 }
 
@@ -74,8 +386,8 @@ fn parse_card(input: &str) -> IResult<&str, Card> {
         input,
         Card {
             id: result.0,
-            winning: result.1 .0,
-            nums: result.1 .1,
+            winning: result.1 .0.into_iter().collect(),
+            nums: result.1 .1.into_iter().collect(),
         },
     ))
 }
@@ -84,28 +396,81 @@ fn parse_card(input: &str) -> IResult<&str, Card> {
 pub fn input_generator(input: &str) -> Vec<Card> {
     let result = separated_list1(char('\n'), parse_card)(input);
 
-    result.ok().expect("Bad parse").1
+    result.expect("Bad parse").1
+}
+
+pub fn solve_part1_with_rules(input: &[Card], rules: &Rules) -> usize {
+    input.iter().map(|card| card.get_score(rules)).sum()
+}
+
+// Cards already in ascending ID order, like the puzzle input, stream straight through the
+// propagator. Anything else has to be sorted into a `CardTable` first, which copies the cards.
+pub fn solve_part2_with_rules(input: &[Card], rules: &Rules) -> Result<usize, CardError> {
+    let mut propagator = CopyPropagator::new(*rules)?;
+
+    if input.windows(2).all(|pair| pair[0].id < pair[1].id) {
+        return input.iter().map(|card| propagator.push(card)).sum();
+    }
+
+    let table = CardTable::new(input)?;
+
+    table.cards().iter().map(|card| propagator.push(card)).sum()
 }
 
 #[aoc(day4, part1)]
 pub fn solve_part1(input: &[Card]) -> usize {
-    input.iter().map(|card| card.get_score()).sum()
+    solve_part1_with_rules(input, &Rules::default())
 }
 
 #[aoc(day4, part2)]
 pub fn solve_part2(input: &[Card]) -> usize {
-    let mut score_map: HashMap<usize, usize> = HashMap::new();
-
-    input
-        .iter()
-        .rev()
-        .map(|card| card.process(&mut score_map))
-        .sum()
+    solve_part2_with_rules(input, &Rules::default()).expect("Bad cards")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::random::Rng;
+
+    // The previous part 2: copies of every card kept in a map, with each card's won IDs collected
+    fn solve_part2_with_map(input: &[Card], rules: &Rules) -> usize {
+        let table = CardTable::new(input).unwrap();
+        let mut copy_map: HashMap<usize, usize> = HashMap::new();
+
+        table
+            .cards()
+            .iter()
+            .map(|card| {
+                let copies = rules.cap_copies(1 + copy_map.get(&card.id).copied().unwrap_or(0));
+
+                let won_card_ids = rules
+                    .won_card_ids(card.id, card.get_part2_score())
+                    .collect::<Vec<_>>();
+                for card_id in won_card_ids {
+                    *copy_map.entry(card_id).or_insert(0) += copies;
+                }
+
+                copies
+            })
+            .sum()
+    }
+
+    fn random_cards(rng: &mut Rng, count: usize) -> Vec<Card> {
+        let mut id = 0;
+
+        (0..count)
+            .map(|_| {
+                // Leave the odd gap in the IDs
+                id += if rng.chance(1, 10) { 2 } else { 1 };
+
+                Card {
+                    id,
+                    winning: (0..5).map(|_| rng.between(1, 20)).collect(),
+                    nums: (0..8).map(|_| rng.between(1, 20)).collect(),
+                }
+            })
+            .collect()
+    }
 
     #[test]
     fn test_parse_num_list() {
@@ -140,8 +505,310 @@ mod tests {
 
         let result = parse_card(input).ok().unwrap().1;
         assert_eq!(result.id, 3);
-        assert_eq!(result.winning, vec![1, 48, 83, 6, 17]);
-        assert_eq!(result.nums, vec![83, 86, 6, 31, 17, 9, 48, 53])
+        assert_eq!(
+            result.winning,
+            vec![1, 48, 83, 6, 17].into_iter().collect::<NumberSet>()
+        );
+        assert_eq!(
+            result.nums,
+            vec![83, 86, 6, 31, 17, 9, 48, 53]
+                .into_iter()
+                .collect::<NumberSet>()
+        )
+    }
+
+    #[test]
+    fn number_set_test() {
+        let set = vec![3, 99, 127, 128, 5000, 3]
+            .into_iter()
+            .collect::<NumberSet>();
+
+        assert!(set.contains(3));
+        assert!(set.contains(99));
+        assert!(set.contains(127));
+        assert!(set.contains(128));
+        assert!(set.contains(5000));
+        assert!(!set.contains(4));
+        assert!(!set.contains(4999));
+    }
+
+    #[test]
+    fn number_set_intersection_len_test() {
+        let a = vec![1, 48, 83, 200, 1000]
+            .into_iter()
+            .collect::<NumberSet>();
+        let b = vec![48, 83, 86, 1000, 1001]
+            .into_iter()
+            .collect::<NumberSet>();
+
+        assert_eq!(a.intersection_len(&b), 3);
+        assert_eq!(b.intersection_len(&a), 3);
+    }
+
+    const EXAMPLE: &str = r"
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+";
+
+    #[test]
+    fn card_table_test() {
+        let input = input_generator(
+            r"
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 8: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+"
+            .trim(),
+        );
+
+        let table = CardTable::new(&input).unwrap();
+        let ids = table.cards().iter().map(|card| card.id).collect::<Vec<_>>();
+
+        assert_eq!(ids, vec![2, 3, 5, 8]);
+        assert_eq!(table.missing(), &[4, 6, 7]);
+    }
+
+    #[test]
+    fn card_table_duplicate_test() {
+        let input = input_generator(
+            r"
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 1:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+"
+            .trim(),
+        );
+
+        assert_eq!(
+            CardTable::new(&input).err(),
+            Some(CardError::DuplicateId(1))
+        );
+    }
+
+    #[test]
+    fn part2_unordered_test() {
+        let mut input = input_generator(EXAMPLE.trim());
+        input.reverse();
+        input.swap(1, 4);
+
+        assert_eq!(solve_part2(&input), 30);
+    }
+
+    #[test]
+    fn part2_missing_cards_test() {
+        // Card 1 wins cards 2 to 5 but 3 and 4 were never dealt
+        let input = input_generator(
+            r"
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+"
+            .trim(),
+        );
+
+        let cascade = Cascade::new(&CardTable::new(&input).unwrap(), &Rules::default()).unwrap();
+        let copies = cascade
+            .entries()
+            .iter()
+            .map(|entry| entry.copies)
+            .collect::<Vec<_>>();
+
+        assert_eq!(copies, vec![1, 2, 2, 1]);
+        assert_eq!(solve_part2(&input), 6);
+    }
+
+    #[test]
+    fn copy_propagator_matches_map_test() {
+        let mut rng = Rng::new(32);
+
+        let rules = [
+            Rules::default(),
+            Rules {
+                stride: 2,
+                ..Rules::default()
+            },
+            Rules {
+                offset: 3,
+                copy_cap: Some(50),
+                ..Rules::default()
+            },
+        ];
+
+        for _ in 0..50 {
+            let cards = random_cards(&mut rng, 100);
+
+            for rules in rules.iter() {
+                assert_eq!(
+                    solve_part2_with_rules(&cards, rules).unwrap(),
+                    solve_part2_with_map(&cards, rules)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn copy_propagator_memory_test() {
+        let mut rng = Rng::new(320);
+        let cards = random_cards(&mut rng, 2000);
+
+        let mut propagator = CopyPropagator::new(Rules::default()).unwrap();
+        for card in cards.iter() {
+            propagator.push(card).unwrap();
+            assert!(propagator.pending.len() <= 5);
+        }
+        assert!(propagator.pending.capacity() < 64);
+    }
+
+    #[test]
+    fn copy_propagator_out_of_order_test() {
+        let input = input_generator(EXAMPLE.trim());
+        let mut propagator = CopyPropagator::new(Rules::default()).unwrap();
+
+        assert_eq!(propagator.push(&input[1]), Ok(1));
+        assert_eq!(propagator.push(&input[1]), Err(CardError::OutOfOrder(2)));
+        assert_eq!(propagator.push(&input[0]), Err(CardError::OutOfOrder(1)));
+        // Card 2 went first, so card 3 only has the copy it won from card 2
+        assert_eq!(propagator.push(&input[2]), Ok(2));
+    }
+
+    #[test]
+    fn cascade_test() {
+        let input = input_generator(EXAMPLE.trim());
+        let cascade = Cascade::new(&CardTable::new(&input).unwrap(), &Rules::default()).unwrap();
+
+        let copies = cascade
+            .entries()
+            .iter()
+            .map(|entry| entry.copies)
+            .collect::<Vec<_>>();
+        assert_eq!(copies, vec![1, 2, 4, 8, 14, 1]);
+        assert_eq!(cascade.total(), solve_part2(&input));
+
+        assert_eq!(
+            cascade.entries()[3],
+            CascadeEntry {
+                id: 4,
+                matches: 1,
+                copies: 8,
+                sources: vec![(1, 1), (2, 2), (3, 4)],
+            }
+        );
+    }
+
+    #[test]
+    fn cascade_table_test() {
+        let input = input_generator(EXAMPLE.trim());
+        let table = Cascade::new(&CardTable::new(&input).unwrap(), &Rules::default())
+            .unwrap()
+            .to_table();
+
+        let expected = r"
+Card | Matches | Copies | Won from
+   1 |       4 |      1 |
+   2 |       2 |      2 | 1 (+1)
+   3 |       2 |      4 | 1 (+1), 2 (+2)
+   4 |       1 |      8 | 1 (+1), 2 (+2), 3 (+4)
+   5 |       0 |     14 | 1 (+1), 3 (+4), 4 (+8)
+   6 |       0 |      1 |
+"
+        .trim();
+
+        assert_eq!(table, expected);
+    }
+
+    #[test]
+    fn cascade_tree_test() {
+        let input = input_generator(EXAMPLE.trim());
+        let tree = Cascade::new(&CardTable::new(&input[..2]).unwrap(), &Rules::default())
+            .unwrap()
+            .to_tree();
+
+        let expected = r"
+Card 1: 1 copy (4 matches)
+└── original (+1)
+Card 2: 2 copies (2 matches)
+├── original (+1)
+└── won by card 1 (+1)
+"
+        .trim();
+
+        assert_eq!(tree, expected);
+    }
+
+    #[test]
+    fn scoring_test() {
+        let scores = |scoring: Scoring| (0..7).map(|m| scoring.score(m)).collect::<Vec<_>>();
+
+        assert_eq!(scores(Scoring::Doubling), vec![0, 1, 2, 4, 8, 16, 32]);
+        assert_eq!(scores(Scoring::Linear), vec![0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(scores(Scoring::Fibonacci), vec![0, 1, 2, 3, 5, 8, 13]);
+    }
+
+    #[test]
+    fn part1_rules_test() {
+        let input = input_generator(EXAMPLE.trim());
+
+        let linear = Rules {
+            scoring: Scoring::Linear,
+            ..Rules::default()
+        };
+        let fibonacci = Rules {
+            scoring: Scoring::Fibonacci,
+            ..Rules::default()
+        };
+
+        assert_eq!(solve_part1_with_rules(&input, &Rules::default()), 13);
+        assert_eq!(solve_part1_with_rules(&input, &linear), 9);
+        assert_eq!(solve_part1_with_rules(&input, &fibonacci), 10);
+    }
+
+    #[test]
+    fn part2_rules_test() {
+        let input = input_generator(EXAMPLE.trim());
+
+        let every_other = Rules {
+            stride: 2,
+            ..Rules::default()
+        };
+        let skip_next = Rules {
+            offset: 2,
+            ..Rules::default()
+        };
+        let capped = Rules {
+            copy_cap: Some(3),
+            ..Rules::default()
+        };
+
+        assert_eq!(solve_part2_with_rules(&input, &every_other), Ok(24));
+        assert_eq!(solve_part2_with_rules(&input, &skip_next), Ok(19));
+        assert_eq!(solve_part2_with_rules(&input, &capped), Ok(13));
+
+        let cascade = Cascade::new(&CardTable::new(&input).unwrap(), &capped).unwrap();
+        assert_eq!(cascade.total(), 13);
+    }
+
+    #[test]
+    fn zero_offset_test() {
+        let input = input_generator(EXAMPLE.trim());
+        let rules = Rules {
+            offset: 0,
+            ..Rules::default()
+        };
+
+        assert_eq!(
+            solve_part2_with_rules(&input, &rules),
+            Err(CardError::ZeroOffset)
+        );
+        assert_eq!(
+            Cascade::new(&CardTable::new(&input).unwrap(), &rules).err(),
+            Some(CardError::ZeroOffset)
+        );
     }
 
     #[test]
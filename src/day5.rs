@@ -1,99 +1,581 @@
 use nom::bytes::complete::tag;
-use nom::character::complete::{char, not_line_ending};
+use nom::character::complete::{alpha1, char};
+use nom::combinator::all_consuming;
+use nom::sequence::{separated_pair, terminated, tuple};
 use nom::{multi::separated_list1, IResult};
+use std::collections::{BTreeSet, HashMap, VecDeque};
 use std::ops::Range;
 
+use crate::interval_map::IntervalMap;
 use crate::parsers::parse_usize;
+use crate::range_set::RangeSet;
+
+pub mod cross_check;
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 // dest, source, length
 struct Map(usize, usize, usize);
 
-impl Map {
-    fn convert(self: &Self, seed: &usize) -> usize {
-        // Difference between dest and source
-        let diff = isize::try_from(self.0).unwrap() - isize::try_from(self.1).unwrap();
-        usize::try_from(isize::try_from(*seed).unwrap() + diff).unwrap()
+fn to_interval_map(maps: &[Map]) -> IntervalMap {
+    IntervalMap::from_triples(maps.iter().map(|Map(dest, src, len)| (*dest, *src, *len)))
+}
+
+#[derive(Debug, PartialEq)]
+pub enum IssueKind {
+    ZeroLength,
+    // The source or destination range runs past `usize::MAX`
+    Overflow,
+    // The source range overlaps the one on the given line of the same map
+    Overlap(usize),
+}
+
+// Problem with the map entry on the given line of the input, counting from 1
+#[derive(Debug, PartialEq)]
+pub struct Issue {
+    pub line: usize,
+    pub kind: IssueKind,
+}
+
+fn validate_maps(maps: &[Map], first_line: usize) -> Vec<Issue> {
+    let mut issues = vec![];
+    let mut ranges = vec![];
+
+    for (i, Map(dest, src, len)) in maps.iter().enumerate() {
+        let line = first_line + i;
+
+        if *len == 0 {
+            issues.push(Issue {
+                line,
+                kind: IssueKind::ZeroLength,
+            });
+            continue;
+        }
+
+        match src.checked_add(*len) {
+            Some(end) if dest.checked_add(*len).is_some() => ranges.push((*src..end, line)),
+            _ => issues.push(Issue {
+                line,
+                kind: IssueKind::Overflow,
+            }),
+        }
     }
 
-    // Create a range of the source for the map
-    fn to_range(self: &Self) -> Range<usize> {
-        self.1..(self.1 + self.2)
+    ranges.sort_by_key(|(range, _)| range.start);
+
+    // The range reaching furthest so far is the one any later range would overlap
+    let mut furthest: Option<(usize, usize)> = None;
+
+    for (range, line) in ranges {
+        if let Some((end, other_line)) = furthest {
+            if range.start < end {
+                issues.push(Issue {
+                    line: line.max(other_line),
+                    kind: IssueKind::Overlap(line.min(other_line)),
+                });
+            }
+        }
+
+        if furthest.is_none_or(|(end, _)| range.end > end) {
+            furthest = Some((range.end, line));
+        }
+    }
+
+    issues.sort_by_key(|issue| issue.line);
+    issues
+}
+
+// Problems with the map entries, the almanac must otherwise parse
+pub fn validate(input: &str) -> Result<Vec<Issue>, AlmanacError> {
+    let (_, sections) = parse_sections(input)?;
+
+    Ok(validate_sections(&sections))
+}
+
+fn validate_sections(sections: &[Section<'_>]) -> Vec<Issue> {
+    sections
+        .iter()
+        .flat_map(|section| validate_maps(&section.maps, section.line + 1))
+        .collect()
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AlmanacError {
+    // A section that isn't a `<source>-to-<destination> map:` heading followed by maps
+    BadSection(String),
+    // More than one map leaves or enters this category
+    Ambiguous(String),
+    // The maps don't join up into a single chain
+    BrokenChain,
+    UnknownCategory(String),
+    // No path of maps from the first category to the second
+    Unreachable(String, String),
+    Invalid(Vec<Issue>),
+    // A seed range whose end doesn't fit in `usize`, as start and length
+    SeedRangeOverflow(usize, usize),
+    // Part 2 reads the seeds in pairs, this one has no length
+    UnpairedSeed(usize),
+}
+
+// A heading and its maps, `line` being the line number of the heading
+struct Section<'a> {
+    line: usize,
+    source: &'a str,
+    destination: &'a str,
+    maps: Vec<Map>,
+}
+
+// Every section has to parse in full, so stray numbers or lines are errors rather than ignored
+fn parse_sections(input: &str) -> Result<(Vec<usize>, Vec<Section<'_>>), AlmanacError> {
+    let sections = input
+        .trim_end_matches('\n')
+        .split("\n\n")
+        .collect::<Vec<_>>();
+
+    let seeds = all_consuming(parse_seeds)(sections[0])
+        .map_err(|_| AlmanacError::BadSection(sections[0].to_string()))?
+        .1;
+
+    // Each section is followed by a blank line
+    let mut line = sections[0].lines().count() + 2;
+    let mut parsed = vec![];
+
+    for section in sections[1..].iter() {
+        let ((source, destination), maps) = all_consuming(parse_map_list_with_heading)(section)
+            .map_err(|_| AlmanacError::BadSection(section.to_string()))?
+            .1;
+
+        parsed.push(Section {
+            line,
+            source,
+            destination,
+            maps,
+        });
+
+        line += section.lines().count() + 1;
+    }
+
+    Ok((seeds, parsed))
+}
+
+// A map entry as written in the almanac, on the given line counting from 1
+#[derive(Debug, PartialEq, Clone)]
+pub struct MapEntry {
+    pub line: usize,
+    pub dest: usize,
+    pub src: usize,
+    pub len: usize,
+}
+
+// One `<source>-to-<destination> map` section
+struct Layer {
+    source: String,
+    destination: String,
+    // The entries in input order, kept to explain conversions
+    entries: Vec<MapEntry>,
+    map: IntervalMap,
+}
+
+impl Layer {
+    // The entry whose source range holds `value`, if any. Validation rules out overlaps, so
+    // there is at most one.
+    fn entry_for(&self, value: usize) -> Option<&MapEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.src <= value && value - entry.src < entry.len)
     }
 }
 
+// How one value went through one layer
+#[derive(Debug, PartialEq)]
+pub struct TraceStep {
+    pub source: String,
+    pub destination: String,
+    pub input: usize,
+    pub output: usize,
+    // `None` when no entry matched and the value passed through unchanged
+    pub entry: Option<MapEntry>,
+}
+
 pub struct Almanac {
     seeds: Vec<usize>,
-    map_list: Vec<Vec<Map>>,
+    // Ordered along the chain of categories, each layer's destination is the next one's source
+    layers: Vec<Layer>,
+    // Every layer composed into a single map from the first category to the last
+    composed: IntervalMap,
+}
+
+// Orders the layers into a single chain, rejecting categories with more than one map in or out
+fn chain_layers(layers: Vec<Layer>) -> Result<Vec<Layer>, AlmanacError> {
+    let mut by_source: HashMap<String, Layer> = HashMap::new();
+    let mut destinations = vec![];
+
+    for layer in layers {
+        if destinations.contains(&layer.destination) {
+            return Err(AlmanacError::Ambiguous(layer.destination));
+        }
+        destinations.push(layer.destination.clone());
+
+        if by_source.contains_key(&layer.source) {
+            return Err(AlmanacError::Ambiguous(layer.source));
+        }
+        by_source.insert(layer.source.clone(), layer);
+    }
+
+    let starts = by_source
+        .keys()
+        .filter(|source| !destinations.contains(source))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    if starts.len() != 1 {
+        return Err(AlmanacError::BrokenChain);
+    }
+
+    let mut chain = vec![];
+    let mut category = starts[0].clone();
+
+    while let Some(layer) = by_source.remove(&category) {
+        category = layer.destination.clone();
+        chain.push(layer);
+    }
+
+    // Anything left over is a cycle that isn't connected to the start
+    if !by_source.is_empty() {
+        return Err(AlmanacError::BrokenChain);
+    }
+
+    Ok(chain)
 }
 
 impl Almanac {
-    fn convert_seed(self: &Self, seed: usize) -> usize {
-        let mut converted = seed;
+    fn new(seeds: Vec<usize>, layers: Vec<Layer>) -> Result<Self, AlmanacError> {
+        let layers = chain_layers(layers)?;
 
-        for list in self.map_list.iter() {
-            let found_map = list.iter().find(|map| map.to_range().contains(&converted));
+        let composed = layers
+            .iter()
+            .fold(IntervalMap::default(), |composed, layer| {
+                composed.compose(&layer.map)
+            });
 
-            if let Some(map) = found_map {
-                converted = map.convert(&converted);
-            }
+        Ok(Almanac {
+            seeds,
+            layers,
+            composed,
+        })
+    }
+
+    pub fn parse(input: &str) -> Result<Self, AlmanacError> {
+        let (seeds, sections) = parse_sections(input)?;
+        let issues = validate_sections(&sections);
+
+        if !issues.is_empty() {
+            return Err(AlmanacError::Invalid(issues));
         }
 
-        converted
+        let layers = sections
+            .iter()
+            .map(|section| Layer {
+                source: section.source.to_string(),
+                destination: section.destination.to_string(),
+                entries: section
+                    .maps
+                    .iter()
+                    .enumerate()
+                    .map(|(i, Map(dest, src, len))| MapEntry {
+                        line: section.line + 1 + i,
+                        dest: *dest,
+                        src: *src,
+                        len: *len,
+                    })
+                    .collect(),
+                map: to_interval_map(&section.maps),
+            })
+            .collect();
+
+        Almanac::new(seeds, layers)
     }
 
-    fn convert_seed_using_map_list(self: &Self, seed: usize, index: usize) -> usize {
-        let found_map = self.map_list[index]
+    // The seeds read as pairs of range start and length
+    pub fn seed_ranges(&self) -> Result<Vec<Range<usize>>, AlmanacError> {
+        self.seeds
+            .chunks(2)
+            .map(|chunk| match *chunk {
+                [start, length] => start
+                    .checked_add(length)
+                    .map(|end| start..end)
+                    .ok_or(AlmanacError::SeedRangeOverflow(start, length)),
+                _ => Err(AlmanacError::UnpairedSeed(chunk[0])),
+            })
+            .collect()
+    }
+
+    // Categories in chain order, from seeds through to locations
+    pub fn categories(&self) -> Vec<&str> {
+        let mut categories = self
+            .layers
             .iter()
-            .find(|map| map.to_range().contains(&seed));
+            .map(|layer| layer.source.as_str())
+            .collect::<Vec<_>>();
 
-        if let Some(map) = found_map {
-            return map.convert(&seed);
+        if let Some(last) = self.layers.last() {
+            categories.push(&last.destination);
         }
 
-        seed
+        categories
     }
 
-    fn convert_seed_range(self: &Self, range: Range<usize>) -> Vec<Range<usize>> {
-        let mut converted = vec![range];
+    // Indices of the layers leading from one category to another, found by a breadth first search
+    // over the maps
+    fn path(&self, from: &str, to: &str) -> Result<Vec<usize>, AlmanacError> {
+        let categories = self.categories();
 
-        for (list_index, list) in self.map_list.iter().enumerate() {
-            let mut converted_ranges = vec![];
+        for category in [from, to] {
+            if !categories.contains(&category) {
+                return Err(AlmanacError::UnknownCategory(category.to_string()));
+            }
+        }
 
-            for r in converted.clone().iter() {
-                let mut valid_map_bounds = list
-                    .iter()
-                    .flat_map(|m| {
-                        let range = m.to_range();
-                        [range.start, range.end]
-                    })
-                    .filter(|b| b > &r.start && b < &r.end)
-                    .collect::<Vec<_>>();
-                valid_map_bounds.push(r.start);
-                valid_map_bounds.push(r.end);
-                valid_map_bounds.sort_unstable();
-                valid_map_bounds.dedup();
-
-                let mut valid_map_ranges = valid_map_bounds
-                    .windows(2)
-                    .map(|w| w[0]..w[1])
-                    .collect::<Vec<Range<usize>>>();
-
-                for ran in valid_map_ranges.iter_mut() {
-                    let converted_start = self.convert_seed_using_map_list(ran.start, list_index);
-                    *ran = converted_start..(converted_start + (ran.len()));
+        let mut came_from: HashMap<&str, Option<usize>> = HashMap::from([(from, None)]);
+        let mut queue = VecDeque::from([from]);
+
+        while let Some(category) = queue.pop_front() {
+            if category == to {
+                break;
+            }
+
+            for (index, layer) in self.layers.iter().enumerate() {
+                if layer.source == category && !came_from.contains_key(layer.destination.as_str()) {
+                    came_from.insert(&layer.destination, Some(index));
+                    queue.push_back(&layer.destination);
                 }
+            }
+        }
+
+        if !came_from.contains_key(to) {
+            return Err(AlmanacError::Unreachable(from.to_string(), to.to_string()));
+        }
+
+        let mut path = vec![];
+        let mut category = to;
+
+        while let Some(Some(index)) = came_from.get(category) {
+            path.push(*index);
+            category = &self.layers[*index].source;
+        }
 
-                converted_ranges.append(&mut valid_map_ranges);
+        path.reverse();
+        Ok(path)
+    }
+
+    pub fn convert(&self, from: &str, to: &str, value: usize) -> Result<usize, AlmanacError> {
+        let path = self.path(from, to)?;
+
+        Ok(path.iter().fold(value, |converted, index| {
+            self.layers[*index].map.get(converted)
+        }))
+    }
+
+    pub fn composed_map(&self) -> &IntervalMap {
+        &self.composed
+    }
+
+    // The composed map written out like one of the almanac's own maps
+    pub fn composed_map_text(&self) -> String {
+        let categories = self.categories();
+
+        format!(
+            "{}-to-{} map:\n{}",
+            categories.first().unwrap_or(&"seed"),
+            categories.last().unwrap_or(&"seed"),
+            self.composed
+        )
+    }
+
+    // Walks the seed through every layer rather than using the composed map
+    pub fn convert_seed(&self, seed: usize) -> usize {
+        self.layers
+            .iter()
+            .fold(seed, |converted, layer| layer.map.get(converted))
+    }
+
+    pub fn convert_seed_range(&self, range: Range<usize>) -> RangeSet {
+        self.layers
+            .iter()
+            .fold(RangeSet::from_iter([range]), |converted, layer| {
+                layer.map.map_set(&converted)
+            })
+    }
+
+    // Values going into layer `layer` that come out of it in `range`, `None` when there is no
+    // such layer
+    pub fn reverse_convert_layer(&self, layer: usize, range: Range<usize>) -> Option<RangeSet> {
+        self.layers
+            .get(layer)
+            .map(|layer| layer.map.preimage(range))
+    }
+
+    // Values going into layer `layer` (0 being seeds) that end up at a location in `range`. The
+    // layer after the last one is the locations themselves. `None` when there is no such layer.
+    pub fn reverse_convert(&self, layer: usize, range: Range<usize>) -> Option<RangeSet> {
+        let layers = self.layers.get(layer..)?;
+
+        Some(
+            layers
+                .iter()
+                .rev()
+                .fold(RangeSet::from_iter([range]), |converted, layer| {
+                    layer.map.preimage_set(&converted)
+                }),
+        )
+    }
+
+    // Ranges stop short of `usize::MAX`, so nothing is found for that location
+    pub fn seeds_for_location(&self, location: usize) -> RangeSet {
+        self.reverse_convert(0, location..location.saturating_add(1))
+            .expect("Layer 0 always exists")
+    }
+
+    // Every layer's conversion of the seed, from seeds through to locations
+    pub fn trace(&self, seed: usize) -> Vec<TraceStep> {
+        let mut value = seed;
+        let mut steps = vec![];
+
+        for layer in self.layers.iter() {
+            let output = layer.map.get(value);
+
+            steps.push(TraceStep {
+                source: layer.source.clone(),
+                destination: layer.destination.clone(),
+                input: value,
+                output,
+                entry: layer.entry_for(value).cloned(),
+            });
+
+            value = output;
+        }
+
+        steps
+    }
+
+    // One line per layer, e.g. `seed 79 -> soil 81 (line 5: 52 50 48)`
+    pub fn trace_text(&self, seed: usize) -> String {
+        self.trace(seed)
+            .iter()
+            .map(|step| {
+                let how = match &step.entry {
+                    Some(entry) => format!(
+                        "line {}: {} {} {}",
+                        entry.line, entry.dest, entry.src, entry.len
+                    ),
+                    None => "identity".to_string(),
+                };
+
+                format!(
+                    "{} {} -> {} {} ({})",
+                    step.source, step.input, step.destination, step.output, how
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    // Graphviz graph with a cluster of range segments per category and an edge for every piece
+    // of a segment that a layer moves into the next category. Segments are cut at the seed
+    // ranges, at every entry's source and destination, and at the images of the previous
+    // category's cuts, so each node maps onto whole nodes of the next category. Everything past
+    // the largest value mentioned in the almanac is left out. Fails if the seeds don't read as
+    // ranges.
+    pub fn to_dot(&self) -> Result<String, AlmanacError> {
+        let mut cuts = vec![BTreeSet::new(); self.layers.len() + 1];
+
+        for range in self.seed_ranges()? {
+            cuts[0].extend([range.start, range.end]);
+        }
+
+        for (i, layer) in self.layers.iter().enumerate() {
+            for entry in layer.entries.iter() {
+                cuts[i].extend([entry.src, entry.src + entry.len]);
+                cuts[i + 1].extend([entry.dest, entry.dest + entry.len]);
+            }
+        }
+
+        let bound = cuts
+            .iter()
+            .filter_map(|c| c.last())
+            .max()
+            .copied()
+            .unwrap_or(0);
+
+        for category in cuts.iter_mut() {
+            category.extend([0, bound]);
+        }
+
+        // Carry the cuts forward so each segment's image lines up with the next category's
+        for (i, layer) in self.layers.iter().enumerate() {
+            let images = segments_between(&cuts[i])
+                .flat_map(|segment| layer.map.map_range(segment))
+                .flat_map(|image| [image.start, image.end])
+                .collect::<Vec<_>>();
+
+            cuts[i + 1].extend(images);
+        }
+
+        let segments = cuts
+            .iter()
+            .map(|c| segments_between(c).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let mut lines = vec![
+            "digraph almanac {".to_string(),
+            "    rankdir=LR;".to_string(),
+            "    node [shape=box];".to_string(),
+        ];
+
+        for (i, category) in self.categories().iter().enumerate() {
+            lines.push(format!("    subgraph cluster_{} {{", i));
+            lines.push(format!("        label=\"{}\";", category));
+
+            for (j, segment) in segments[i].iter().enumerate() {
+                lines.push(format!(
+                    "        c{}_{} [label=\"{}..{}\"];",
+                    i, j, segment.start, segment.end
+                ));
             }
 
-            converted = converted_ranges;
+            lines.push("    }".to_string());
+        }
+
+        for (i, layer) in self.layers.iter().enumerate() {
+            for (j, segment) in segments[i].iter().enumerate() {
+                let style = match layer.entry_for(segment.start) {
+                    Some(entry) => format!("label=\"line {}\"", entry.line),
+                    None => "style=dashed".to_string(),
+                };
+
+                for image in layer.map.map_range(segment.clone()) {
+                    let next = &segments[i + 1];
+                    let first = next.partition_point(|s| s.end <= image.start);
+                    let last = next.partition_point(|s| s.start < image.end);
+
+                    for k in first..last {
+                        lines.push(format!("    c{}_{} -> c{}_{} [{}];", i, j, i + 1, k, style));
+                    }
+                }
+            }
         }
 
-        converted
+        lines.push("}".to_string());
+        Ok(lines.join("\n"))
     }
 }
 
+// Consecutive ranges between sorted cut points
+fn segments_between(cuts: &BTreeSet<usize>) -> impl Iterator<Item = Range<usize>> + '_ {
+    cuts.iter()
+        .zip(cuts.iter().skip(1))
+        .map(|(start, end)| *start..*end)
+}
+
 fn parse_seeds(input: &str) -> IResult<&str, Vec<usize>> {
     let (input, _) = tag("seeds: ")(input)?;
 
@@ -101,36 +583,32 @@ fn parse_seeds(input: &str) -> IResult<&str, Vec<usize>> {
 }
 
 fn parse_map(input: &str) -> IResult<&str, Map> {
-    let (input, result) = separated_list1(char(' '), parse_usize)(input)?;
+    let (input, (dest, _, src, _, len)) =
+        tuple((parse_usize, char(' '), parse_usize, char(' '), parse_usize))(input)?;
 
-    IResult::Ok((input, Map(result[0], result[1], result[2])))
+    IResult::Ok((input, Map(dest, src, len)))
 }
 
 fn parse_map_list(input: &str) -> IResult<&str, Vec<Map>> {
     separated_list1(char('\n'), parse_map)(input)
 }
 
-fn parse_map_list_with_heading(input: &str) -> IResult<&str, Vec<Map>> {
-    let (input, _) = not_line_ending(input)?;
+// Source and destination categories of a `seed-to-soil map:` heading
+fn parse_heading(input: &str) -> IResult<&str, (&str, &str)> {
+    terminated(separated_pair(alpha1, tag("-to-"), alpha1), tag(" map:"))(input)
+}
+
+fn parse_map_list_with_heading(input: &str) -> IResult<&str, ((&str, &str), Vec<Map>)> {
+    let (input, heading) = parse_heading(input)?;
     let (input, _) = char('\n')(input)?;
+    let (input, map_list) = parse_map_list(input)?;
 
-    parse_map_list(input)
+    IResult::Ok((input, (heading, map_list)))
 }
 
 #[aoc_generator(day5)]
 pub fn input_generator(input: &str) -> Almanac {
-    let sections = input.split("\n\n").collect::<Vec<_>>();
-
-    let seeds = parse_seeds(sections[0]).ok().unwrap().1;
-
-    let map_list = (1..sections.len())
-        .map(|i| {
-            let section = sections[i];
-            parse_map_list_with_heading(section).ok().unwrap().1
-        })
-        .collect();
-
-    Almanac { seeds, map_list }
+    Almanac::parse(input).expect("Bad almanac")
 }
 
 #[aoc(day5, part1)]
@@ -138,27 +616,60 @@ pub fn solve_part1(input: &Almanac) -> usize {
     input
         .seeds
         .iter()
-        .map(|seed| input.convert_seed(*seed))
+        .map(|seed| input.composed.get(*seed))
         .min()
         .unwrap_or(0)
 }
 
 #[aoc(day5, part2)]
 pub fn solve_part2(input: &Almanac) -> usize {
-    let seed_ranges: Vec<Range<usize>> = input
-        .seeds
-        .chunks(2)
-        .map(|chunk| chunk[0]..(chunk[0] + chunk[1]))
-        .collect();
+    let seeds = input
+        .seed_ranges()
+        .expect("Bad seed ranges")
+        .into_iter()
+        .collect::<RangeSet>();
 
-    seed_ranges
-        .iter()
-        .flat_map(|r| input.convert_seed_range(r.clone()))
-        .flat_map(|r| [r.start, r.end])
-        .min()
-        .expect("Woops")
+    input.composed.map_set(&seeds).min().expect("No seeds")
 }
 
+// The puzzle's example, shared with the tests of the submodules
+#[cfg(test)]
+const EXAMPLE: &str = r"
+seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4
+";
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -179,6 +690,28 @@ mod tests {
         assert_eq!(result, Map(3224558845, 3632370674, 5378086))
     }
 
+    #[test]
+    fn bad_map_line_test() {
+        let heading = "seeds: 1 2\n\nseed-to-soil map:\n";
+        let bad_section = |lines: &str| {
+            Err(AlmanacError::BadSection(format!(
+                "seed-to-soil map:\n{}",
+                lines
+            )))
+        };
+
+        // Too few numbers, too many, and a line that isn't a map at all
+        for lines in ["1 2", "1 2 3 4", "1 2 3\nfoo"] {
+            let input = format!("{}{}", heading, lines);
+
+            assert_eq!(Almanac::parse(&input).err(), bad_section(lines).err());
+            assert_eq!(validate(&input), bad_section(lines));
+        }
+
+        // A trailing newline is fine
+        assert!(Almanac::parse(&format!("{}1 2 3\n", heading)).is_ok());
+    }
+
     #[test]
     fn parse_map_list_with_heading_test() {
         let input = r"
@@ -189,7 +722,409 @@ seed-to-soil map:
         .trim();
 
         let result = parse_map_list_with_heading(input).ok().unwrap().1;
-        assert_eq!(result, vec![Map(11, 22, 33), Map(44, 55, 66)])
+        assert_eq!(
+            result,
+            (("seed", "soil"), vec![Map(11, 22, 33), Map(44, 55, 66)])
+        )
+    }
+
+    #[test]
+    fn parse_heading_test() {
+        let input = "light-to-temperature map:";
+
+        let result = parse_heading(input).ok().unwrap().1;
+        assert_eq!(result, ("light", "temperature"));
+    }
+
+    #[test]
+    fn categories_test() {
+        let input = input_generator(EXAMPLE.trim());
+
+        assert_eq!(
+            input.categories(),
+            vec![
+                "seed",
+                "soil",
+                "fertilizer",
+                "water",
+                "light",
+                "temperature",
+                "humidity",
+                "location"
+            ]
+        );
+    }
+
+    #[test]
+    fn convert_between_categories_test() {
+        let input = input_generator(EXAMPLE.trim());
+
+        // Seed 79 is soil 81, fertilizer 81, water 81, light 74, temperature 78, humidity 78,
+        // location 82
+        assert_eq!(input.convert("seed", "soil", 79), Ok(81));
+        assert_eq!(input.convert("soil", "light", 81), Ok(74));
+        assert_eq!(input.convert("seed", "location", 79), Ok(82));
+        assert_eq!(input.convert("water", "water", 81), Ok(81));
+        assert_eq!(
+            input.convert("location", "seed", 82),
+            Err(AlmanacError::Unreachable(
+                "location".to_string(),
+                "seed".to_string()
+            ))
+        );
+        assert_eq!(
+            input.convert("seed", "planet", 79),
+            Err(AlmanacError::UnknownCategory("planet".to_string()))
+        );
+    }
+
+    #[test]
+    fn unordered_sections_test() {
+        let input = r"
+seeds: 79 14 55 13
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+seed-to-soil map:
+50 98 2
+52 50 48
+"
+        .trim();
+
+        let almanac = Almanac::parse(input).ok().unwrap();
+        assert_eq!(almanac.categories(), vec!["seed", "soil", "fertilizer"]);
+        assert_eq!(almanac.convert_seed(79), 81);
+        assert_eq!(almanac.convert_seed(14), 53);
+    }
+
+    #[test]
+    fn broken_chain_test() {
+        let input = r"
+seeds: 79
+
+seed-to-soil map:
+50 98 2
+
+fertilizer-to-water map:
+49 53 8
+"
+        .trim();
+
+        assert_eq!(Almanac::parse(input).err(), Some(AlmanacError::BrokenChain));
+    }
+
+    #[test]
+    fn ambiguous_chain_test() {
+        let input = r"
+seeds: 79
+
+seed-to-soil map:
+50 98 2
+
+seed-to-water map:
+49 53 8
+"
+        .trim();
+
+        assert_eq!(
+            Almanac::parse(input).err(),
+            Some(AlmanacError::Ambiguous("seed".to_string()))
+        );
+    }
+
+    #[test]
+    fn cyclic_chain_test() {
+        let input = r"
+seeds: 79
+
+seed-to-soil map:
+50 98 2
+
+water-to-light map:
+49 53 8
+
+light-to-water map:
+49 53 8
+"
+        .trim();
+
+        assert_eq!(Almanac::parse(input).err(), Some(AlmanacError::BrokenChain));
+    }
+
+    #[test]
+    fn composed_map_test() {
+        let input = input_generator(EXAMPLE.trim());
+
+        for seed in 0..150 {
+            assert_eq!(input.composed_map().get(seed), input.convert_seed(seed));
+        }
+    }
+
+    #[test]
+    fn composed_map_text_test() {
+        let input = input_generator(EXAMPLE.trim());
+        let text = input.composed_map_text();
+
+        assert!(text.starts_with("seed-to-location map:\n"));
+
+        let (heading, maps) = parse_map_list_with_heading(&text).ok().unwrap().1;
+        assert_eq!(heading, ("seed", "location"));
+        assert_eq!(&to_interval_map(&maps), input.composed_map());
+    }
+
+    #[test]
+    fn seeds_for_location_test() {
+        let input = input_generator(EXAMPLE.trim());
+        let seeds = input.seeds_for_location(46);
+
+        assert!(seeds.contains(82));
+        for seed in seeds.ranges().iter().flat_map(|r| r.clone()) {
+            assert_eq!(input.convert_seed(seed), 46);
+        }
+
+        assert!(input.seeds_for_location(usize::MAX).is_empty());
+    }
+
+    #[test]
+    fn reverse_convert_test() {
+        let input = input_generator(EXAMPLE.trim());
+        let locations = 40..60;
+
+        let seeds = input.reverse_convert(0, locations.clone()).unwrap();
+        assert_eq!(seeds, input.composed_map().preimage(locations.clone()));
+
+        for seed in 0..200 {
+            assert_eq!(
+                seeds.contains(seed),
+                locations.contains(&input.convert_seed(seed))
+            );
+        }
+
+        // Soil values are the inputs of the second layer
+        let soils = input.reverse_convert(1, locations.clone()).unwrap();
+        for seed in 0..200 {
+            let soil = input.convert("seed", "soil", seed).unwrap();
+            assert_eq!(
+                soils.contains(soil),
+                locations.contains(&input.convert_seed(seed))
+            );
+        }
+
+        // Past the last layer the values are locations already, and there's nothing beyond that
+        assert_eq!(
+            input.reverse_convert(7, locations.clone()),
+            Some(RangeSet::from_iter([locations.clone()]))
+        );
+        assert_eq!(input.reverse_convert(8, locations), None);
+    }
+
+    #[test]
+    fn reverse_convert_layer_test() {
+        let input = input_generator(EXAMPLE.trim());
+
+        assert_eq!(
+            input.reverse_convert_layer(0, 50..53).unwrap().ranges(),
+            &[50..51, 98..100]
+        );
+        assert_eq!(input.reverse_convert_layer(7, 50..53), None);
+    }
+
+    #[test]
+    fn convert_seed_range_test() {
+        let input = input_generator(EXAMPLE.trim());
+
+        // Seed 82 is the lowest location in 79..93, and the end of the range isn't a candidate
+        let locations = input.convert_seed_range(79..93);
+        assert_eq!(locations.min(), Some(46));
+        assert_eq!(locations.len(), 14);
+
+        for seed in 79..93 {
+            assert!(locations.contains(input.convert_seed(seed)));
+        }
+    }
+
+    #[test]
+    fn trace_test() {
+        let input = input_generator(EXAMPLE.trim());
+        let steps = input.trace(79);
+
+        assert_eq!(steps.len(), 7);
+        assert_eq!(
+            steps[0],
+            TraceStep {
+                source: "seed".to_string(),
+                destination: "soil".to_string(),
+                input: 79,
+                output: 81,
+                entry: Some(MapEntry {
+                    line: 5,
+                    dest: 52,
+                    src: 50,
+                    len: 48
+                }),
+            }
+        );
+        assert_eq!(steps[1].entry, None);
+        assert_eq!(steps.last().map(|step| step.output), Some(82));
+
+        for pair in steps.windows(2) {
+            assert_eq!(pair[0].output, pair[1].input);
+            assert_eq!(pair[0].destination, pair[1].source);
+        }
+    }
+
+    #[test]
+    fn trace_text_test() {
+        let input = input_generator(EXAMPLE.trim());
+
+        assert_eq!(
+            input.trace_text(14),
+            r"
+seed 14 -> soil 14 (identity)
+soil 14 -> fertilizer 53 (line 10: 39 0 15)
+fertilizer 53 -> water 49 (line 13: 49 53 8)
+water 49 -> light 42 (line 20: 18 25 70)
+light 42 -> temperature 42 (identity)
+temperature 42 -> humidity 43 (line 29: 1 0 69)
+humidity 43 -> location 43 (identity)
+"
+            .trim()
+        );
+    }
+
+    #[test]
+    fn to_dot_test() {
+        let input = input_generator("seeds: 0 4\n\nseed-to-soil map:\n10 2 3");
+
+        // Seeds 2..4 move up to 10..12, which the identity gap past 5 also reaches
+        assert_eq!(
+            input.to_dot().unwrap(),
+            r#"
+digraph almanac {
+    rankdir=LR;
+    node [shape=box];
+    subgraph cluster_0 {
+        label="seed";
+        c0_0 [label="0..2"];
+        c0_1 [label="2..4"];
+        c0_2 [label="4..5"];
+        c0_3 [label="5..13"];
+    }
+    subgraph cluster_1 {
+        label="soil";
+        c1_0 [label="0..2"];
+        c1_1 [label="2..5"];
+        c1_2 [label="5..10"];
+        c1_3 [label="10..12"];
+        c1_4 [label="12..13"];
+    }
+    c0_0 -> c1_0 [style=dashed];
+    c0_1 -> c1_3 [label="line 4"];
+    c0_2 -> c1_4 [label="line 4"];
+    c0_3 -> c1_2 [style=dashed];
+    c0_3 -> c1_3 [style=dashed];
+    c0_3 -> c1_4 [style=dashed];
+}
+"#
+            .trim()
+        );
+    }
+
+    #[test]
+    fn to_dot_example_test() {
+        let input = input_generator(EXAMPLE.trim());
+        let dot = input.to_dot().unwrap();
+
+        assert!(dot.starts_with("digraph almanac {"));
+        assert!(dot.contains("label=\"location\";"));
+        // The seed range 79..93 is one node and the range it maps to is cut out in the soils
+        assert!(dot.contains("c0_4 [label=\"79..93\"];"));
+        assert!(dot.contains("[label=\"81..95\"];"));
+    }
+
+    #[test]
+    fn to_dot_unpaired_seed_test() {
+        let input = input_generator("seeds: 0 4 7\n\nseed-to-soil map:\n10 2 3");
+
+        assert_eq!(input.to_dot(), Err(AlmanacError::UnpairedSeed(7)));
+    }
+
+    #[test]
+    fn validate_test() {
+        let input = r"
+seeds: 79 14
+
+seed-to-soil map:
+50 98 2
+52 50 48
+10 60 5
+0 0 0
+
+soil-to-fertilizer map:
+0 15 37
+1 18446744073709551610 10
+37 52 2
+"
+        .trim();
+
+        let issues = validate(input).ok().unwrap();
+        assert_eq!(
+            issues,
+            vec![
+                Issue {
+                    line: 6,
+                    kind: IssueKind::Overlap(5)
+                },
+                Issue {
+                    line: 7,
+                    kind: IssueKind::ZeroLength
+                },
+                Issue {
+                    line: 11,
+                    kind: IssueKind::Overflow
+                },
+            ]
+        );
+
+        assert_eq!(
+            Almanac::parse(input).err(),
+            Some(AlmanacError::Invalid(issues))
+        );
+    }
+
+    #[test]
+    fn validate_example_test() {
+        assert_eq!(validate(EXAMPLE.trim()), Ok(vec![]));
+    }
+
+    #[test]
+    fn seed_ranges_test() {
+        let input = input_generator(EXAMPLE.trim());
+        assert_eq!(input.seed_ranges(), Ok(vec![79..93, 55..68]));
+
+        let input = input_generator("seeds: 1 18446744073709551615\n\nseed-to-soil map:\n1 2 3");
+        assert_eq!(
+            input.seed_ranges(),
+            Err(AlmanacError::SeedRangeOverflow(1, usize::MAX))
+        );
+
+        let input = input_generator("seeds: 1 2 3\n\nseed-to-soil map:\n1 2 3");
+        assert_eq!(input.seed_ranges(), Err(AlmanacError::UnpairedSeed(3)));
+    }
+
+    #[test]
+    fn convert_near_max_test() {
+        let input = input_generator(
+            "seeds: 18446744073709551614\n\nseed-to-soil map:\n0 18446744073709551613 2",
+        );
+
+        assert_eq!(input.convert_seed(usize::MAX - 2), 0);
+        assert_eq!(input.convert_seed(usize::MAX - 1), 1);
+        assert_eq!(input.convert_seed(usize::MAX), usize::MAX);
+        assert_eq!(solve_part1(&input), 1);
     }
 
     #[test]
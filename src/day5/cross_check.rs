@@ -0,0 +1,330 @@
+// Checks `Almanac::convert_seed_range` against converting every seed in the range one at a time
+// with `Almanac::convert_seed`, on small random almanacs. Failures are shrunk to a minimal
+// almanac and written out in the puzzle's format so they can go straight into a regression test.
+use std::fmt;
+use std::ops::Range;
+
+use super::Almanac;
+use crate::random::Rng;
+use crate::range_set::RangeSet;
+
+const CATEGORIES: [&str; 8] = [
+    "seed",
+    "soil",
+    "fertilizer",
+    "water",
+    "light",
+    "temperature",
+    "humidity",
+    "location",
+];
+
+// Largest value used by the random almanacs
+const MAX_VALUE: usize = 64;
+
+// Plain description of an almanac that is easy to generate and shrink
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlmanacSpec {
+    pub seeds: Vec<usize>,
+    // `(dest, src, len)` entries of each map in chain order, from seeds to locations
+    pub maps: Vec<Vec<(usize, usize, usize)>>,
+}
+
+impl AlmanacSpec {
+    // Between 1 and 7 maps, each with non-overlapping sources, and up to 3 seed ranges
+    pub fn random(rng: &mut Rng) -> Self {
+        let seeds = (0..rng.between(1, 3))
+            .flat_map(|_| [rng.below(MAX_VALUE), rng.between(1, 16)])
+            .collect();
+
+        let maps = (0..rng.between(1, CATEGORIES.len() - 1))
+            .map(|_| {
+                let mut entries = vec![];
+                let mut src = rng.below(8);
+
+                while src < MAX_VALUE {
+                    let len = rng.between(1, 8);
+
+                    if rng.chance(1, 2) {
+                        entries.push((rng.below(MAX_VALUE), src, len));
+                    }
+
+                    src += len;
+                }
+
+                // Every map needs at least one entry to parse
+                if entries.is_empty() {
+                    entries.push((rng.below(MAX_VALUE), 0, 1));
+                }
+
+                entries
+            })
+            .collect();
+
+        AlmanacSpec { seeds, maps }
+    }
+
+    pub fn to_almanac(&self) -> Option<Almanac> {
+        Almanac::parse(&self.to_string()).ok()
+    }
+
+    // Every almanac one step smaller than this one. Some of them may not be valid.
+    fn shrink_candidates(&self) -> Vec<AlmanacSpec> {
+        let mut candidates = vec![];
+
+        // Drop a seed range
+        if self.seeds.len() > 2 {
+            for pair in (0..self.seeds.len()).step_by(2) {
+                let mut candidate = self.clone();
+                candidate
+                    .seeds
+                    .drain(pair..(pair + 2).min(self.seeds.len()));
+                candidates.push(candidate);
+            }
+        }
+
+        // Drop a map
+        if self.maps.len() > 1 {
+            for map in 0..self.maps.len() {
+                let mut candidate = self.clone();
+                candidate.maps.remove(map);
+                candidates.push(candidate);
+            }
+        }
+
+        // Drop an entry
+        for (map, entries) in self.maps.iter().enumerate() {
+            if entries.len() > 1 {
+                for entry in 0..entries.len() {
+                    let mut candidate = self.clone();
+                    candidate.maps[map].remove(entry);
+                    candidates.push(candidate);
+                }
+            }
+        }
+
+        // Make a seed range start earlier or be shorter
+        for (i, &value) in self.seeds.iter().enumerate() {
+            let smallest = if i % 2 == 0 { 0 } else { 1 };
+
+            for smaller in smaller_values(value, smallest) {
+                let mut candidate = self.clone();
+                candidate.seeds[i] = smaller;
+                candidates.push(candidate);
+            }
+        }
+
+        // Make an entry's numbers smaller
+        for (map, entries) in self.maps.iter().enumerate() {
+            for (entry, &(dest, src, len)) in entries.iter().enumerate() {
+                let mut replace = |replacement: (usize, usize, usize)| {
+                    let mut candidate = self.clone();
+                    candidate.maps[map][entry] = replacement;
+                    candidates.push(candidate);
+                };
+
+                for smaller in smaller_values(dest, 0) {
+                    replace((smaller, src, len));
+                }
+                for smaller in smaller_values(src, 0) {
+                    replace((dest, smaller, len));
+                }
+                for smaller in smaller_values(len, 1) {
+                    replace((dest, src, smaller));
+                }
+            }
+        }
+
+        candidates
+    }
+}
+
+// Smaller values to try in place of `value`, most aggressive first
+fn smaller_values(value: usize, smallest: usize) -> Vec<usize> {
+    if value <= smallest {
+        return vec![];
+    }
+
+    let mut values = vec![smallest, smallest + (value - smallest) / 2, value - 1];
+
+    values.dedup();
+    values
+}
+
+// The almanac in the puzzle's text format
+impl fmt::Display for AlmanacSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let seeds = self
+            .seeds
+            .iter()
+            .map(|seed| seed.to_string())
+            .collect::<Vec<_>>();
+        write!(f, "seeds: {}", seeds.join(" "))?;
+
+        for (i, entries) in self.maps.iter().enumerate() {
+            write!(f, "\n\n{}-to-{} map:", CATEGORIES[i], CATEGORIES[i + 1])?;
+
+            for (dest, src, len) in entries {
+                write!(f, "\n{} {} {}", dest, src, len)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Mismatch {
+    pub seeds: Range<usize>,
+    // Locations from converting the seeds one by one
+    pub expected: RangeSet,
+    // Locations from converting the whole range
+    pub actual: RangeSet,
+}
+
+// First seed range where the range conversion disagrees with converting every seed
+pub fn cross_check(almanac: &Almanac) -> Option<Mismatch> {
+    for seeds in almanac.seed_ranges().ok()? {
+        let expected = seeds
+            .clone()
+            .map(|seed| {
+                let location = almanac.convert_seed(seed);
+                location..(location + 1)
+            })
+            .collect::<RangeSet>();
+        let actual = almanac.convert_seed_range(seeds.clone());
+
+        if expected != actual {
+            return Some(Mismatch {
+                seeds,
+                expected,
+                actual,
+            });
+        }
+    }
+
+    None
+}
+
+// Repeatedly replaces the almanac with a smaller one that still fails, until none of the smaller
+// candidates do
+pub fn shrink<F: Fn(&AlmanacSpec) -> bool>(spec: AlmanacSpec, fails: F) -> AlmanacSpec {
+    let mut spec = spec;
+
+    while let Some(smaller) = spec
+        .shrink_candidates()
+        .into_iter()
+        .find(|candidate| fails(candidate))
+    {
+        spec = smaller;
+    }
+
+    spec
+}
+
+fn fails_cross_check(spec: &AlmanacSpec) -> bool {
+    spec.to_almanac()
+        .is_some_and(|almanac| cross_check(&almanac).is_some())
+}
+
+// Cross checks `iterations` random almanacs. On failure returns a report with the minimal
+// almanac in the puzzle's format.
+pub fn run(seed: u64, iterations: usize) -> Result<(), String> {
+    let mut rng = Rng::new(seed);
+
+    for _ in 0..iterations {
+        let spec = AlmanacSpec::random(&mut rng);
+
+        if fails_cross_check(&spec) {
+            let minimal = shrink(spec, fails_cross_check);
+            let mismatch = cross_check(&minimal.to_almanac().unwrap()).unwrap();
+
+            return Err(format!(
+                "Range conversion of seeds {:?} gave {:?}, converting each seed gave {:?}\n\n{}",
+                mismatch.seeds,
+                mismatch.actual.ranges(),
+                mismatch.expected.ranges(),
+                minimal
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day5::{input_generator, EXAMPLE};
+
+    #[test]
+    fn random_almanacs_parse_test() {
+        let mut rng = Rng::new(5);
+
+        for _ in 0..100 {
+            let spec = AlmanacSpec::random(&mut rng);
+            assert!(spec.to_almanac().is_some(), "{}", spec);
+        }
+    }
+
+    #[test]
+    fn display_test() {
+        let spec = AlmanacSpec {
+            seeds: vec![79, 14],
+            maps: vec![vec![(50, 98, 2), (52, 50, 48)], vec![(0, 15, 37)]],
+        };
+
+        let expected = r"
+seeds: 79 14
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+"
+        .trim();
+
+        assert_eq!(spec.to_string(), expected);
+    }
+
+    #[test]
+    fn shrink_test() {
+        let mut rng = Rng::new(39);
+        let spec = AlmanacSpec::random(&mut rng);
+
+        // Stand-in for a bug that needs an entry at least 3 long
+        let fails = |spec: &AlmanacSpec| {
+            spec.to_almanac().is_some()
+                && spec
+                    .maps
+                    .iter()
+                    .any(|entries| entries.iter().any(|(_, _, len)| *len >= 3))
+        };
+        assert!(fails(&spec));
+
+        let minimal = shrink(spec, fails);
+        assert_eq!(
+            minimal,
+            AlmanacSpec {
+                seeds: vec![0, 1],
+                maps: vec![vec![(0, 0, 3)]],
+            }
+        );
+    }
+
+    #[test]
+    fn cross_check_example_test() {
+        let almanac = input_generator(EXAMPLE.trim());
+
+        assert_eq!(cross_check(&almanac), None);
+    }
+
+    #[test]
+    fn cross_check_random_test() {
+        if let Err(report) = run(2023, 500) {
+            panic!("{}", report);
+        }
+    }
+}
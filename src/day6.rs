@@ -19,16 +19,251 @@ fn parse_distance(input: &str) -> IResult<&str, Vec<usize>> {
     separated_list1(many1(char(' ')), parse_usize)(input)
 }
 
-// Smaller root is .0
-fn quadratic_roots(a: i128, b: i128, c: i128) -> (f64, f64) {
-    let x0 = ((-1 * b) as f64 + f64::sqrt((b.pow(2) - (4 * a * c)) as f64)) as f64 / (2 * a) as f64;
-    let x1 = ((-1 * b) as f64 - f64::sqrt((b.pow(2) - (4 * a * c)) as f64)) as f64 / (2 * a) as f64;
+// How the boat moves. The default is the puzzle's boat, which gains 1 mm/ms of speed for every
+// millisecond the button is held and starts from standstill.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Kinematics {
+    // Speed gained per millisecond held
+    pub acceleration: u128,
+    // Speed the boat has without holding the button at all
+    pub initial_speed: u128,
+    pub speed_cap: Option<u128>,
+    // Milliseconds lost to charging whenever the button is held at all
+    pub charge_penalty: u128,
+}
+
+impl Default for Kinematics {
+    fn default() -> Self {
+        Kinematics {
+            acceleration: 1,
+            initial_speed: 0,
+            speed_cap: None,
+            charge_penalty: 0,
+        }
+    }
+}
+
+impl Kinematics {
+    // `None` when the speed doesn't fit in a `u128`, which a cap always prevents
+    fn speed(&self, hold: u128) -> Option<u128> {
+        let speed = self
+            .acceleration
+            .checked_mul(hold)
+            .and_then(|gain| gain.checked_add(self.initial_speed));
+
+        match (speed, self.speed_cap) {
+            (Some(speed), Some(cap)) => Some(speed.min(cap)),
+            (None, Some(cap)) => Some(cap),
+            (speed, None) => speed,
+        }
+    }
+
+    // Distance covered holding for `hold` out of `time` milliseconds, `None` when it doesn't fit
+    // in a `u128`
+    pub fn distance(&self, time: u128, hold: u128) -> Option<u128> {
+        let penalty = if hold > 0 { self.charge_penalty } else { 0 };
+        let moving = time.saturating_sub(hold).saturating_sub(penalty);
+
+        if moving == 0 {
+            return Some(0);
+        }
+
+        self.speed(hold).and_then(|speed| speed.checked_mul(moving))
+    }
+
+    fn beats(&self, time: u128, record: u128, hold: u128) -> bool {
+        self.distance(time, hold)
+            .is_none_or(|distance| distance > record)
+    }
+
+    // Whether the speed can reach the cap within the race, after which distance is no longer a
+    // quadratic in the hold
+    fn cap_binds(&self, time: u128) -> bool {
+        match self.speed_cap {
+            Some(cap) => self.speed(time).is_none_or(|speed| speed >= cap),
+            None => false,
+        }
+    }
+
+    // Number of holds, from 0 to `time` milliseconds, that go further than the record
+    pub fn count_ways(&self, time: u128, record: u128) -> u128 {
+        // Holding for 0 skips the penalty, so it is checked on its own
+        let standing = self.beats(time, record, 0) as u128;
+
+        standing
+            + self
+                .holding_wins(time, record)
+                .map_or(0, |(low, high)| high - low + 1)
+    }
+
+    // Shortest and longest winning holds of at least 1
+    fn holding_wins(&self, time: u128, record: u128) -> Option<(u128, u128)> {
+        self.closed_form_holds(time, record)
+            .unwrap_or_else(|| self.searched_holds(time, record))
+    }
+
+    // Shortest and longest winning holds, including not holding at all
+    fn winning_holds(&self, time: u128, record: u128) -> Option<(u128, u128)> {
+        let holds = self.holding_wins(time, record);
+
+        if !self.beats(time, record, 0) {
+            return holds;
+        }
+
+        Some((0, holds.map_or(0, |(_, high)| high)))
+    }
+
+    // The shortest hold that goes furthest and how far it goes, `None` when that distance doesn't
+    // fit in a `u128`
+    fn best_hold(&self, time: u128) -> Option<(u128, u128)> {
+        let standing = self.distance(time, 0)?;
+
+        if time == 0 {
+            return Some((0, standing));
+        }
+
+        let peak = self.peak_hold(time);
+        let distance = self.distance(time, peak)?;
+
+        if distance > standing {
+            Some((peak, distance))
+        } else {
+            Some((0, standing))
+        }
+    }
 
-    if x0 < x1 {
-        return (x0, x1);
+    // First hold of at least 1 that goes at least as far as the next one. The distance rises to
+    // a single peak and then falls, so it is found by binary search on the slope.
+    fn peak_hold(&self, time: u128) -> u128 {
+        // Anything too big to fit counts as further than anything that does
+        let distance = |hold| self.distance(time, hold).unwrap_or(u128::MAX);
+
+        let (mut low, mut high) = (1, time);
+        while low < high {
+            let mid = low + (high - low) / 2;
+
+            if distance(mid) >= distance(mid + 1) {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+
+        low
+    }
+
+    // Over holds of at least 1 the distance is `(v + a * hold) * (t - hold)`, where `t` is the
+    // time left after the penalty, which beats the record between the roots of
+    // `a * hold² - (a * t - v) * hold + (record - v * t) = 0`. The roots are estimated with an
+    // integer square root and then settled with exact checks. `None` when the cap can bind or
+    // the arithmetic doesn't fit in an `i128`.
+    fn closed_form_holds(&self, time: u128, record: u128) -> Option<Option<(u128, u128)>> {
+        if self.acceleration == 0 || self.cap_binds(time) {
+            return None;
+        }
+
+        // The holds are clamped to the race, which needs the time itself to fit
+        let time_i = i128::try_from(time).ok()?;
+
+        let Some(moving) = time.checked_sub(self.charge_penalty).filter(|t| *t > 0) else {
+            return Some(None);
+        };
+
+        let a = i128::try_from(self.acceleration).ok()?;
+        let v = i128::try_from(self.initial_speed).ok()?;
+        let t = i128::try_from(moving).ok()?;
+        let r = i128::try_from(record).ok()?;
+
+        let b = a.checked_mul(t)?.checked_sub(v)?;
+        let c = r.checked_sub(v.checked_mul(t)?)?;
+        let discriminant = b
+            .checked_mul(b)?
+            .checked_sub(a.checked_mul(4)?.checked_mul(c)?)?;
+
+        if discriminant < 0 {
+            return Some(None);
+        }
+
+        let root = discriminant.isqrt();
+        let low_estimate = (b - root).div_euclid(2 * a);
+        let high_estimate = (b + root).div_euclid(2 * a);
+
+        // The peak of the parabola, the better of the two holds around its vertex
+        let vertex = b.div_euclid(2 * a).clamp(1, t) as u128;
+        let peak = if self.distance(time, vertex + 1) > self.distance(time, vertex) {
+            vertex + 1
+        } else {
+            vertex
+        };
+
+        if !self.beats(time, record, peak) {
+            return Some(None);
+        }
+
+        let mut low = low_estimate.clamp(1, peak as i128) as u128;
+        let mut high = high_estimate.clamp(peak as i128, time_i) as u128;
+
+        while low > 1 && self.beats(time, record, low - 1) {
+            low -= 1;
+        }
+        while !self.beats(time, record, low) {
+            low += 1;
+        }
+        while high < time && self.beats(time, record, high + 1) {
+            high += 1;
+        }
+        while !self.beats(time, record, high) {
+            high -= 1;
+        }
+
+        Some(Some((low, high)))
+    }
+
+    // Exact search over holds of at least 1, by binary search on the record either side of the
+    // peak
+    fn searched_holds(&self, time: u128, record: u128) -> Option<(u128, u128)> {
+        if time == 0 {
+            return None;
+        }
+
+        let peak = self.peak_hold(time);
+
+        if !self.beats(time, record, peak) {
+            return None;
+        }
+
+        // Shortest winning hold on the rising side
+        let (mut low, mut high) = (1, peak);
+        while low < high {
+            let mid = low + (high - low) / 2;
+
+            if self.beats(time, record, mid) {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        let shortest = low;
+
+        // Longest winning hold on the falling side
+        let (mut low, mut high) = (peak, time);
+        while low < high {
+            let mid = low + (high - low).div_ceil(2);
+
+            if self.beats(time, record, mid) {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+
+        Some((shortest, low))
     }
+}
 
-    return (x1, x0);
+// Number of whole-millisecond holds that go further than the record with the puzzle's boat
+pub fn count_ways(time: u128, record: u128) -> u128 {
+    Kinematics::default().count_ways(time, record)
 }
 
 pub struct Races {
@@ -44,40 +279,114 @@ fn parse_races(input: &str) -> IResult<&str, Races> {
     IResult::Ok((input, Races { time, distance }))
 }
 
-#[aoc_generator(day6)]
-pub fn input_generator(input: &str) -> Races {
-    parse_races(input).ok().unwrap().1
+// Everything about one race's winning holds
+#[derive(Debug, PartialEq)]
+pub struct RaceReport {
+    pub time: u128,
+    pub record: u128,
+    pub ways: u128,
+    // Shortest and longest winning holds, `None` when the record can't be beaten
+    pub min_winning_hold: Option<u128>,
+    pub max_winning_hold: Option<u128>,
+    // The shortest hold that goes furthest, and how far that is. Both are `None` when the best
+    // distance doesn't fit in a `u128`, which for the puzzle's boat is any race of 2^65 ms or more.
+    pub optimal_hold: Option<u128>,
+    pub max_distance: Option<u128>,
+    // How far the best hold goes past the record, negative when it falls short. `None` when the
+    // difference doesn't fit in an `i128`.
+    pub margin: Option<i128>,
+    // The best distance is only ever tied, so it is the smallest record nobody can beat
+    pub unbeatable_record: Option<u128>,
 }
-#[aoc(day6, part1)]
-
-pub fn solve_part1(input: &Races) -> f64 {
-    let a = -1;
-
-    let mut ways = 1.0;
 
-    for index in 0..input.time.len() {
-        let time = input.time[index];
-        let distance = input.distance[index];
-
-        let b = time as i128;
-        let c = -1 * distance as i128;
-
-        let (x0, x1) = quadratic_roots(a, b, c);
+fn margin(distance: u128, record: u128) -> Option<i128> {
+    match distance.checked_sub(record) {
+        Some(excess) => i128::try_from(excess).ok(),
+        None => i128::try_from(record - distance)
+            .ok()
+            .map(|shortfall| -shortfall),
+    }
+}
 
-        let min = x0.floor() + 1.0;
-        let max = x1.ceil() - 1.0;
+impl RaceReport {
+    pub fn new(time: u128, record: u128, kinematics: &Kinematics) -> Self {
+        let holds = kinematics.winning_holds(time, record);
+        let best = kinematics.best_hold(time);
+        let max_distance = best.map(|(_, distance)| distance);
+
+        RaceReport {
+            time,
+            record,
+            ways: kinematics.count_ways(time, record),
+            min_winning_hold: holds.map(|(low, _)| low),
+            max_winning_hold: holds.map(|(_, high)| high),
+            optimal_hold: best.map(|(hold, _)| hold),
+            max_distance,
+            margin: max_distance.and_then(|distance| margin(distance, record)),
+            unbeatable_record: max_distance,
+        }
+    }
+}
 
-        let _ways = ((max - min) + 1.0).round();
-        ways *= _ways;
+impl Races {
+    pub fn reports(&self, kinematics: &Kinematics) -> Vec<RaceReport> {
+        self.time
+            .iter()
+            .zip(self.distance.iter())
+            .map(|(time, distance)| RaceReport::new(*time as u128, *distance as u128, kinematics))
+            .collect()
     }
 
-    ways
+    pub fn report_table(&self, kinematics: &Kinematics) -> String {
+        let mut lines = vec![
+            "Race | Time | Record | Ways | Winning holds | Best hold | Best distance | Margin | Unbeatable"
+                .to_string(),
+        ];
+
+        for (i, report) in self.reports(kinematics).iter().enumerate() {
+            let holds = match (report.min_winning_hold, report.max_winning_hold) {
+                (Some(min), Some(max)) => format!("{}..={}", min, max),
+                _ => "-".to_string(),
+            };
+            let or_dash = |value: Option<String>| value.unwrap_or("-".to_string());
+            let optimal_hold = or_dash(report.optimal_hold.map(|hold| hold.to_string()));
+            let max_distance = or_dash(report.max_distance.map(|d| d.to_string()));
+            let margin = or_dash(report.margin.map(|margin| margin.to_string()));
+            let unbeatable = or_dash(report.unbeatable_record.map(|r| r.to_string()));
+
+            lines.push(format!(
+                "{:>4} | {:>4} | {:>6} | {:>4} | {:>13} | {:>9} | {:>13} | {:>6} | {:>10}",
+                i + 1,
+                report.time,
+                report.record,
+                report.ways,
+                holds,
+                optimal_hold,
+                max_distance,
+                margin,
+                unbeatable
+            ));
+        }
+
+        lines.join("\n")
+    }
 }
 
-#[aoc(day6, part2)]
-pub fn solve_part2(input: &Races) -> f64 {
-    let a = -1;
+#[aoc_generator(day6)]
+pub fn input_generator(input: &str) -> Races {
+    parse_races(input).ok().unwrap().1
+}
+pub fn solve_part1_with_kinematics(input: &Races, kinematics: &Kinematics) -> usize {
+    input
+        .time
+        .iter()
+        .zip(input.distance.iter())
+        .map(|(time, distance)| kinematics.count_ways(*time as u128, *distance as u128))
+        .map(|ways| usize::try_from(ways).expect("Too many ways to win"))
+        .product()
+}
 
+pub fn solve_part2_with_kinematics(input: &Races, kinematics: &Kinematics) -> usize {
     let time = input.time.iter().map(|t| t.to_string()).collect::<String>();
     let distance = input
         .distance
@@ -85,22 +394,25 @@ pub fn solve_part2(input: &Races) -> f64 {
         .map(|t| t.to_string())
         .collect::<String>();
 
-    let b = time.parse::<i128>().unwrap();
-    let c = -1 * distance.parse::<i128>().unwrap();
+    let ways = kinematics.count_ways(time.parse().unwrap(), distance.parse().unwrap());
 
-    let (x0, x1) = quadratic_roots(a, b, c);
-
-    let min = x0.floor() + 1.0;
-    let max = x1.ceil() - 1.0;
+    usize::try_from(ways).expect("Too many ways to win")
+}
 
-    let _ways = ((max - min) + 1.0).round();
+#[aoc(day6, part1)]
+pub fn solve_part1(input: &Races) -> usize {
+    solve_part1_with_kinematics(input, &Kinematics::default())
+}
 
-    _ways
+#[aoc(day6, part2)]
+pub fn solve_part2(input: &Races) -> usize {
+    solve_part2_with_kinematics(input, &Kinematics::default())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::random::Rng;
 
     #[test]
     fn parse_time_test() {
@@ -131,6 +443,343 @@ Distance:  9  40  200
         assert_eq!(result.distance, vec![9, 40, 200]);
     }
 
+    #[test]
+    fn count_ways_test() {
+        assert_eq!(count_ways(7, 9), 4);
+        assert_eq!(count_ways(15, 40), 8);
+        assert_eq!(count_ways(30, 200), 9);
+        assert_eq!(count_ways(0, 0), 0);
+        assert_eq!(count_ways(1, 0), 0);
+        assert_eq!(count_ways(2, 0), 1);
+    }
+
+    #[test]
+    fn count_ways_perfect_square_test() {
+        // Roots at exactly 3 and 7, neither of which beats the record
+        assert_eq!(count_ways(10, 21), 3);
+        // Roots at 4 and 6
+        assert_eq!(count_ways(10, 24), 1);
+        // A single root at 5 only ties the record
+        assert_eq!(count_ways(10, 25), 0);
+    }
+
+    #[test]
+    fn count_ways_brute_force_test() {
+        for time in 0..60u128 {
+            for record in 0..1000 {
+                let expected = (0..=time)
+                    .filter(|hold| hold * (time - hold) > record)
+                    .count();
+                assert_eq!(count_ways(time, record), expected as u128);
+            }
+        }
+    }
+
+    #[test]
+    fn count_ways_large_test() {
+        // The best hold of 2^62 reaches 2^124, its neighbours one less and the next ones four less
+        let time = 1 << 63;
+        assert_eq!(count_ways(time, (1 << 124) - 1), 1);
+        assert_eq!(count_ways(time, (1 << 124) - 2), 3);
+        assert_eq!(count_ways(time, (1 << 124) - 4), 3);
+        assert_eq!(count_ways(time, (1 << 124) - 5), 5);
+        assert_eq!(count_ways(time, 1 << 124), 0);
+
+        // `time²` overflows from here on, every hold but the first and last beats a zero record
+        let time = 1 << 64;
+        assert_eq!(count_ways(time, (1 << 126) - 1), 1);
+        assert_eq!(count_ways(time, 0), time - 1);
+        assert_eq!(count_ways(u128::MAX, 0), u128::MAX - 1);
+    }
+
+    #[test]
+    fn reports_test() {
+        let input = input_generator(EXAMPLE.trim());
+        let reports = input.reports(&Kinematics::default());
+
+        assert_eq!(
+            reports[0],
+            RaceReport {
+                time: 7,
+                record: 9,
+                ways: 4,
+                min_winning_hold: Some(2),
+                max_winning_hold: Some(5),
+                optimal_hold: Some(3),
+                max_distance: Some(12),
+                margin: Some(3),
+                unbeatable_record: Some(12),
+            }
+        );
+
+        let ways = reports.iter().map(|report| report.ways).product::<u128>();
+        assert_eq!(ways, 288);
+    }
+
+    #[test]
+    fn unbeatable_report_test() {
+        let report = RaceReport::new(10, 25, &Kinematics::default());
+
+        assert_eq!(report.ways, 0);
+        assert_eq!(report.min_winning_hold, None);
+        assert_eq!(report.max_winning_hold, None);
+        assert_eq!(report.max_distance, Some(25));
+        assert_eq!(report.margin, Some(0));
+
+        assert_eq!(
+            RaceReport::new(10, 30, &Kinematics::default()).margin,
+            Some(-5)
+        );
+        assert_eq!(RaceReport::new(10, 24, &Kinematics::default()).ways, 1);
+    }
+
+    #[test]
+    fn large_margin_report_test() {
+        // The best distance is 9 * 2^124, past `i128::MAX`
+        let time = 3 << 63;
+        let max_distance = 9 << 124;
+
+        assert_eq!(
+            RaceReport::new(time, 0, &Kinematics::default()).margin,
+            None
+        );
+        assert_eq!(
+            RaceReport::new(time, max_distance - 5, &Kinematics::default()).margin,
+            Some(5)
+        );
+        // Falls short of the largest record by 7 * 2^124 - 1
+        assert_eq!(
+            RaceReport::new(time, u128::MAX, &Kinematics::default()).margin,
+            Some(1 - (7 << 124))
+        );
+    }
+
+    #[test]
+    fn overflowing_distance_report_test() {
+        let report = RaceReport::new(u128::MAX, 0, &Kinematics::default());
+
+        assert_eq!(report.ways, u128::MAX - 1);
+        assert_eq!(report.min_winning_hold, Some(1));
+        assert_eq!(report.optimal_hold, None);
+        assert_eq!(report.max_distance, None);
+        assert_eq!(report.margin, None);
+        assert_eq!(report.unbeatable_record, None);
+    }
+
+    #[test]
+    fn kinematics_report_test() {
+        // Capped at 3 mm/ms, holds of 2 and 3 reach 10 and 12
+        let capped = Kinematics {
+            speed_cap: Some(3),
+            ..Kinematics::default()
+        };
+        let report = RaceReport::new(7, 9, &capped);
+        assert_eq!(report.ways, 2);
+        assert_eq!(report.min_winning_hold, Some(2));
+        assert_eq!(report.max_winning_hold, Some(3));
+        assert_eq!(report.optimal_hold, Some(3));
+        assert_eq!(report.max_distance, Some(12));
+
+        // Already moving at 2 mm/ms, standing still reaches 14 and holds of 2 and 3 reach 20
+        let rolling = Kinematics {
+            initial_speed: 2,
+            ..Kinematics::default()
+        };
+        let report = RaceReport::new(7, 9, &rolling);
+        assert_eq!(report.ways, 6);
+        assert_eq!(report.min_winning_hold, Some(0));
+        assert_eq!(report.max_winning_hold, Some(5));
+        assert_eq!(report.optimal_hold, Some(2));
+        assert_eq!(report.max_distance, Some(20));
+        assert_eq!(report.margin, Some(11));
+
+        // Rolling at 10 mm/ms, standing still reaches 70 and any hold at most 66
+        let fast_start = Kinematics {
+            initial_speed: 10,
+            ..Kinematics::default()
+        };
+        let report = RaceReport::new(7, 80, &fast_start);
+        assert_eq!(report.optimal_hold, Some(0));
+        assert_eq!(report.max_distance, Some(70));
+        assert_eq!(report.ways, 0);
+    }
+
+    #[test]
+    fn report_table_test() {
+        let input = input_generator(EXAMPLE.trim());
+
+        assert_eq!(
+            input.report_table(&Kinematics::default()),
+            r"
+Race | Time | Record | Ways | Winning holds | Best hold | Best distance | Margin | Unbeatable
+   1 |    7 |      9 |    4 |         2..=5 |         3 |            12 |      3 |         12
+   2 |   15 |     40 |    8 |        4..=11 |         7 |            56 |     16 |         56
+   3 |   30 |    200 |    9 |       11..=19 |        15 |           225 |     25 |        225
+"
+            .trim()
+        );
+    }
+
+    // Every hold tried one at a time
+    fn count_ways_brute_force(kinematics: &Kinematics, time: u128, record: u128) -> u128 {
+        (0..=time)
+            .filter(|hold| {
+                let penalty = if *hold > 0 {
+                    kinematics.charge_penalty
+                } else {
+                    0
+                };
+                let mut speed = kinematics.initial_speed + kinematics.acceleration * hold;
+                if let Some(cap) = kinematics.speed_cap {
+                    speed = speed.min(cap);
+                }
+
+                speed * time.saturating_sub(hold + penalty) > record
+            })
+            .count() as u128
+    }
+
+    #[test]
+    fn kinematics_default_test() {
+        let kinematics = Kinematics::default();
+
+        for time in 0..60 {
+            for record in 0..1000 {
+                assert_eq!(
+                    kinematics.count_ways(time, record),
+                    count_ways(time, record)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn kinematics_variants_test() {
+        // Holds of 2 and 3 reach 10 and 12, anything longer is held back by the cap
+        let capped = Kinematics {
+            speed_cap: Some(3),
+            ..Kinematics::default()
+        };
+        assert_eq!(capped.count_ways(7, 9), 2);
+
+        // The best hold of 3 only ties the record once a millisecond goes on charging
+        let penalised = Kinematics {
+            charge_penalty: 1,
+            ..Kinematics::default()
+        };
+        assert_eq!(penalised.count_ways(7, 9), 0);
+
+        // Already moving at 2 mm/ms, holding for up to 5 wins, including not holding at all
+        let rolling = Kinematics {
+            initial_speed: 2,
+            ..Kinematics::default()
+        };
+        assert_eq!(rolling.count_ways(7, 9), 6);
+
+        let fast = Kinematics {
+            acceleration: 3,
+            ..Kinematics::default()
+        };
+        assert_eq!(fast.count_ways(7, 9), 6);
+    }
+
+    #[test]
+    fn kinematics_brute_force_test() {
+        let mut rng = Rng::new(6);
+
+        for _ in 0..2000 {
+            let kinematics = Kinematics {
+                acceleration: rng.below(4) as u128,
+                initial_speed: rng.below(4) as u128,
+                speed_cap: rng.chance(1, 2).then(|| rng.below(20) as u128),
+                charge_penalty: rng.below(4) as u128,
+            };
+            let time = rng.below(40) as u128;
+            let record = rng.below(300) as u128;
+
+            assert_eq!(
+                kinematics.count_ways(time, record),
+                count_ways_brute_force(&kinematics, time, record),
+                "{:?} time {} record {}",
+                kinematics,
+                time,
+                record
+            );
+
+            // Wherever the closed form applies it has to agree with the search
+            if let Some(holds) = kinematics.closed_form_holds(time, record) {
+                assert_eq!(holds, kinematics.searched_holds(time, record));
+            }
+        }
+    }
+
+    #[test]
+    fn kinematics_large_test() {
+        let kinematics = Kinematics::default();
+        let time = 1 << 64;
+
+        // Too big for the closed form, so these go through the search
+        assert_eq!(kinematics.count_ways(time, (1 << 126) - 1), 1);
+        assert_eq!(kinematics.count_ways(time, 0), time - 1);
+
+        let capped = Kinematics {
+            speed_cap: Some(1 << 40),
+            ..Kinematics::default()
+        };
+        let record = (1 << 103) - 1;
+        let (low, high) = capped.searched_holds(time, record).unwrap();
+
+        // Capped at 2^40 mm/ms, holding until 2^63 still leaves enough time to reach 2^103
+        assert_eq!(high, 1 << 63);
+        assert!(low < 1 << 40);
+        assert!(capped.distance(time, low - 1).unwrap() <= record);
+        assert!(capped.distance(time, low).unwrap() > record);
+        assert!(capped.distance(time, high + 1).unwrap() <= record);
+        assert_eq!(capped.count_ways(time, record), high - low + 1);
+
+        // Races too long for an `i128`, where a penalty brings the moving time back into range.
+        // After 2^127 ms of charging, holds of 1 to 99 still leave time to move.
+        let penalised = Kinematics {
+            charge_penalty: 1 << 127,
+            ..Kinematics::default()
+        };
+        assert_eq!(penalised.count_ways((1 << 127) + 100, 0), 99);
+
+        // Only 5 ms are left after charging, so holds of 1 to 4 go 4, 6, 6 and 4
+        let penalised = Kinematics {
+            charge_penalty: u128::MAX - 5,
+            ..Kinematics::default()
+        };
+        assert_eq!(penalised.count_ways(u128::MAX, 0), 4);
+        assert_eq!(penalised.count_ways(u128::MAX, 4), 2);
+        assert_eq!(penalised.count_ways(u128::MAX, 6), 0);
+    }
+
+    #[test]
+    fn part1_kinematics_test() {
+        let input = input_generator(EXAMPLE.trim());
+        let capped = Kinematics {
+            speed_cap: Some(3),
+            ..Kinematics::default()
+        };
+
+        assert_eq!(
+            solve_part1_with_kinematics(&input, &Kinematics::default()),
+            288
+        );
+        assert_eq!(
+            solve_part1_with_kinematics(&input, &capped),
+            (0..3)
+                .map(|i| capped.count_ways(input.time[i] as u128, input.distance[i] as u128))
+                .product::<u128>() as usize
+        );
+    }
+
+    const EXAMPLE: &str = r"
+Time:      7  15   30
+Distance:  9  40  200
+";
+
     #[test]
     fn part1_test() {
         let input_str1: &str = r"
@@ -140,7 +789,7 @@ Distance:  9  40  200
         .trim();
 
         let input = input_generator(input_str1);
-        assert_eq!(solve_part1(&input), 288.0);
+        assert_eq!(solve_part1(&input), 288);
     }
 
     #[test]
@@ -152,6 +801,6 @@ Distance:  9  40  200
         .trim();
 
         let input = input_generator(input_str2);
-        assert_eq!(solve_part2(&input), 71503.0);
+        assert_eq!(solve_part2(&input), 71503);
     }
 }
@@ -1,13 +1,17 @@
 use std::cmp::Ordering;
+use std::fmt;
 
-use nom::bytes::complete::tag;
-use nom::character::complete::{alphanumeric1, char};
-use nom::{multi::separated_list1, IResult};
+use nom::character::complete::{anychar, char};
+use nom::combinator::{all_consuming, map_res};
+use nom::{multi::many0, IResult};
 
 use crate::parsers::parse_usize;
 
+pub mod distribution;
+pub mod poker;
+
 #[derive(PartialOrd, PartialEq, Debug, Copy, Clone, Eq, Ord)]
-enum Card {
+pub enum Card {
     Two,
     Three,
     Four,
@@ -23,29 +27,53 @@ enum Card {
     A,
 }
 
-impl From<char> for Card {
-    fn from(value: char) -> Self {
-        return match value {
-            '2' => Card::Two,
-            '3' => Card::Three,
-            '4' => Card::Four,
-            '5' => Card::Five,
-            '6' => Card::Six,
-            '7' => Card::Seven,
-            '8' => Card::Eight,
-            '9' => Card::Nine,
-            'T' => Card::T,
-            'J' => Card::J,
-            'Q' => Card::Q,
-            'K' => Card::K,
-            'A' => Card::A,
-            _ => unreachable!(),
+impl TryFrom<char> for Card {
+    type Error = char;
+
+    fn try_from(value: char) -> Result<Self, char> {
+        match value {
+            '2' => Ok(Card::Two),
+            '3' => Ok(Card::Three),
+            '4' => Ok(Card::Four),
+            '5' => Ok(Card::Five),
+            '6' => Ok(Card::Six),
+            '7' => Ok(Card::Seven),
+            '8' => Ok(Card::Eight),
+            '9' => Ok(Card::Nine),
+            'T' => Ok(Card::T),
+            'J' => Ok(Card::J),
+            'Q' => Ok(Card::Q),
+            'K' => Ok(Card::K),
+            'A' => Ok(Card::A),
+            _ => Err(value),
+        }
+    }
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symbol = match self {
+            Card::Two => '2',
+            Card::Three => '3',
+            Card::Four => '4',
+            Card::Five => '5',
+            Card::Six => '6',
+            Card::Seven => '7',
+            Card::Eight => '8',
+            Card::Nine => '9',
+            Card::T => 'T',
+            Card::J => 'J',
+            Card::Q => 'Q',
+            Card::K => 'K',
+            Card::A => 'A',
         };
+
+        write!(f, "{}", symbol)
     }
 }
 
 #[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Copy, Clone)]
-enum Type {
+pub enum Type {
     HighCard,
     Pair,
     TwoPair,
@@ -55,136 +83,455 @@ enum Type {
     FiveOfAKind,
 }
 
-fn get_cards_type(cards: &[Card]) -> Type {
-    let mut counts = [0; 13];
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Type::HighCard => "High card",
+            Type::Pair => "One pair",
+            Type::TwoPair => "Two pair",
+            Type::ThreeOfAKind => "Three of a kind",
+            Type::FullHouse => "Full house",
+            Type::FourOfAKind => "Four of a kind",
+            Type::FiveOfAKind => "Five of a kind",
+        };
+
+        write!(f, "{}", name)
+    }
+}
 
-    for card in cards {
-        counts[*card as usize] += 1;
+// Sizes of the groups of matching cards, largest first. Wildcards join the largest group, which
+// always gives the strongest type.
+fn group_sizes(cards: &[Card], wildcard: Option<Card>) -> Vec<usize> {
+    let mut sorted = cards
+        .iter()
+        .filter(|card| Some(**card) != wildcard)
+        .copied()
+        .collect::<Vec<_>>();
+    sorted.sort();
+
+    let mut groups = sorted
+        .chunk_by(|a, b| a == b)
+        .map(|group| group.len())
+        .collect::<Vec<_>>();
+    groups.sort_by(|a, b| b.cmp(a));
+
+    let wildcards = cards.len() - sorted.len();
+    match groups.first_mut() {
+        Some(largest) => *largest += wildcards,
+        None if wildcards > 0 => groups.push(wildcards),
+        None => {}
     }
 
-    let mut pairs = 0;
-    let mut threes = 0;
-    let mut fours = 0;
-    let mut fives = 0;
+    groups
+}
 
-    for count in counts.iter() {
-        match count {
-            2 => pairs += 1,
-            3 => threes += 1,
-            4 => fours += 1,
-            5 => fives += 1,
-            _ => {}
-        }
+// Hands of any size are typed by their two largest groups
+fn get_groups_type(groups: &[usize]) -> Type {
+    let largest = groups.first().copied().unwrap_or(0);
+    let second = groups.get(1).copied().unwrap_or(0);
+
+    match (largest, second) {
+        (5.., _) => Type::FiveOfAKind,
+        (4, _) => Type::FourOfAKind,
+        (3, 2..) => Type::FullHouse,
+        (3, _) => Type::ThreeOfAKind,
+        (2, 2) => Type::TwoPair,
+        (2, _) => Type::Pair,
+        _ => Type::HighCard,
     }
+}
 
-    if fives == 1 {
-        return Type::FiveOfAKind;
+// Type of a hand without wildcards
+pub fn get_cards_type(cards: &[Card]) -> Type {
+    get_groups_type(&group_sizes(cards, None))
+}
+
+// Order in which cards are compared when two hands have the same type
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TieBreak {
+    // First cards, then second cards and so on, as in Camel Cards
+    InOrder,
+    // Last cards first
+    Reversed,
+    // Each hand's strongest card first, then its next strongest and so on
+    Strongest,
+}
+
+// A variant of Camel Cards. The default is the rules of part 1.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rules {
+    // Every card from weakest to strongest
+    pub ranking: Vec<Card>,
+    // Card that counts as whatever makes the strongest type
+    pub wildcard: Option<Card>,
+    pub hand_size: usize,
+    pub tie_break: TieBreak,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules::standard()
     }
+}
 
-    if fours == 1 {
-        return Type::FourOfAKind;
+const CARDS: [Card; 13] = [
+    Card::Two,
+    Card::Three,
+    Card::Four,
+    Card::Five,
+    Card::Six,
+    Card::Seven,
+    Card::Eight,
+    Card::Nine,
+    Card::T,
+    Card::J,
+    Card::Q,
+    Card::K,
+    Card::A,
+];
+
+impl Rules {
+    pub fn standard() -> Self {
+        Rules {
+            ranking: CARDS.to_vec(),
+            wildcard: None,
+            hand_size: 5,
+            tie_break: TieBreak::InOrder,
+        }
+    }
+
+    // Part 2, where `J` is a joker that ranks below every other card
+    pub fn jokers() -> Self {
+        let mut ranking = vec![Card::J];
+        ranking.extend(CARDS.iter().filter(|card| **card != Card::J));
+
+        Rules {
+            ranking,
+            wildcard: Some(Card::J),
+            ..Rules::standard()
+        }
     }
 
-    if threes == 1 && pairs == 1 {
-        return Type::FullHouse;
+    // Panics on a card the ranking leaves out
+    fn rank(&self, card: Card) -> usize {
+        self.ranking
+            .iter()
+            .position(|ranked| *ranked == card)
+            .expect("Card isn't ranked")
     }
 
-    if threes == 1 {
-        return Type::ThreeOfAKind;
+    pub fn hand_type(&self, cards: &[Card]) -> Type {
+        get_groups_type(&group_sizes(cards, self.wildcard))
     }
 
-    if pairs == 2 {
-        return Type::TwoPair;
+    // Card ranks in the order they are compared
+    fn tie_break_ranks(&self, cards: &[Card]) -> Vec<usize> {
+        let mut ranks = cards
+            .iter()
+            .map(|card| self.rank(*card))
+            .collect::<Vec<_>>();
+
+        match self.tie_break {
+            TieBreak::InOrder => {}
+            TieBreak::Reversed => ranks.reverse(),
+            TieBreak::Strongest => ranks.sort_by(|a, b| b.cmp(a)),
+        }
+
+        ranks
     }
 
-    if pairs == 1 {
-        return Type::Pair;
+    // The hand packed into an integer that sorts the same way as `compare_hands` for hands of the
+    // same size: the type in the top bits, then 4 bits for each card's rank in tie-break order
+    pub fn sort_key(&self, cards: &[Card]) -> u64 {
+        assert!(cards.len() <= 15, "Too many cards to pack into a key");
+
+        self.tie_break_ranks(cards)
+            .iter()
+            .fold(self.hand_type(cards) as u64, |key, rank| {
+                (key << 4) | *rank as u64
+            })
     }
 
-    return Type::HighCard;
+    pub fn compare_hands(&self, a: &Hand, b: &Hand) -> Ordering {
+        let comparison = self.hand_type(&a.cards).cmp(&self.hand_type(&b.cards));
+
+        if comparison != Ordering::Equal {
+            return comparison;
+        }
+
+        self.tie_break_ranks(&a.cards)
+            .cmp(&self.tie_break_ranks(&b.cards))
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct Hand {
     cards: Vec<Card>,
     bid: usize,
 }
 
-fn compare_cards(a: &[Card], b: &[Card]) -> Ordering {
-    let mut result = Ordering::Equal;
+impl Hand {
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
 
-    for (a, b) in a.iter().zip(b) {
-        let comparison = a.cmp(b);
-        if comparison != Ordering::Equal {
-            result = comparison;
-            break;
-        }
+    pub fn bid(&self) -> usize {
+        self.bid
     }
+}
 
-    result
+#[derive(Debug, PartialEq)]
+pub enum HandErrorKind {
+    // Not one of `23456789TJQKA`, or a card the rules don't rank
+    BadCard(char),
+    // The hand has this many cards rather than the rules' hand size
+    WrongSize(usize),
+    MissingBid,
+    BadBid,
+    // Not one of the suits `cdhs`
+    BadSuit(char),
+    // The same card and suit turn up twice in one poker hand
+    DuplicateCard,
 }
 
-fn compare_hands(a: &&Hand, b: &&Hand) -> Ordering {
-    let a_type = get_cards_type(&a.cards);
-    let b_type = get_cards_type(&b.cards);
+// Problem with a hand at the given line and column of the input, both counting from 1
+#[derive(Debug, PartialEq)]
+pub struct HandError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: HandErrorKind,
+}
 
-    let comparison = a_type.cmp(&b_type);
+// Column of `rest` within `input`, counting from 1
+fn column(input: &str, rest: &str) -> usize {
+    input[..input.len() - rest.len()].chars().count() + 1
+}
 
-    if comparison != Ordering::Equal {
-        return comparison;
-    }
+// The space and bid that follow a hand's cards, `rest` being the part of `input` after them
+fn parse_bid(line: usize, input: &str, rest: &str) -> Result<usize, HandError> {
+    let error = |rest, kind| HandError {
+        line,
+        column: column(input, rest),
+        kind,
+    };
 
-    compare_cards(&a.cards, &b.cards)
+    let (rest, _) = char::<_, ()>(' ')(rest).map_err(|_| error(rest, HandErrorKind::MissingBid))?;
+    let (_, bid) =
+        all_consuming(parse_usize)(rest).map_err(|_| error(rest, HandErrorKind::BadBid))?;
+
+    Ok(bid)
 }
 
+// Leading card characters, stopping at the first one that isn't a card
 fn parse_cards(input: &str) -> IResult<&str, Vec<Card>> {
-    let (input, result) = alphanumeric1(input)?;
+    many0(map_res(anychar, Card::try_from))(input)
+}
 
-    let cards = result.chars().map(|c| Card::from(c)).collect::<Vec<Card>>();
+fn parse_hand(line: usize, input: &str, rules: &Rules) -> Result<Hand, HandError> {
+    let error = |column, kind| HandError { line, column, kind };
 
-    IResult::Ok((input, cards))
-}
+    let (rest, cards) = parse_cards(input).expect("Parsing cards can't fail");
 
-fn parse_hand(input: &str) -> IResult<&str, Hand> {
-    let (input, cards) = parse_cards(input)?;
-    let (input, _) = char(' ')(input)?;
-    let (input, bid) = parse_usize(input)?;
+    // The first card the rules don't rank, or whatever stopped the cards
+    if let Some(i) = cards.iter().position(|card| !rules.ranking.contains(card)) {
+        return Err(error(
+            i + 1,
+            HandErrorKind::BadCard(input.chars().nth(i).unwrap()),
+        ));
+    }
+    if let Some(c) = rest.chars().next().filter(|c| *c != ' ') {
+        return Err(error(column(input, rest), HandErrorKind::BadCard(c)));
+    }
+
+    if cards.len() != rules.hand_size {
+        let column = cards.len().min(rules.hand_size) + 1;
+        return Err(error(column, HandErrorKind::WrongSize(cards.len())));
+    }
 
-    IResult::Ok((input, Hand { cards, bid }))
+    let bid = parse_bid(line, input, rest)?;
+
+    Ok(Hand { cards, bid })
+}
+
+// One hand and bid per line, with only the cards and hand size the rules allow
+pub fn parse_hands(input: &str, rules: &Rules) -> Result<Vec<Hand>, HandError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| parse_hand(i + 1, line, rules))
+        .collect()
 }
 
 #[aoc_generator(day7)]
 pub fn input_generator(input: &str) -> Vec<Hand> {
-    separated_list1(tag("\n"), parse_hand)(input)
-        .ok()
-        .unwrap()
-        .1
+    parse_hands(input, &Rules::standard()).expect("Bad hands")
 }
 
-#[aoc(day7, part1)]
-pub fn solve_part1(input: &[Hand]) -> usize {
-    let mut sorted = vec![];
-    for hand in input {
-        sorted.push(hand);
-    }
+// Hands of any kind from weakest to strongest, each key worked out once. Tied hands keep their
+// input order.
+pub fn rank_by_key<T, K: Ord>(input: &[T], key: impl Fn(&T) -> K) -> Vec<&T> {
+    let mut keyed = input
+        .iter()
+        .map(|hand| (key(hand), hand))
+        .collect::<Vec<_>>();
 
-    sorted.sort_by(compare_hands);
+    keyed.sort_by(|(a, _), (b, _)| a.cmp(b));
 
-    sorted
+    keyed.into_iter().map(|(_, hand)| hand).collect()
+}
+
+// Each hand's bid multiplied by its rank, the weakest hand being rank 1
+pub fn winnings_by_key<T, K: Ord>(
+    input: &[T],
+    key: impl Fn(&T) -> K,
+    bid: impl Fn(&T) -> usize,
+) -> usize {
+    rank_by_key(input, key)
         .iter()
         .enumerate()
-        .map(|(i, hand)| (i + 1) * hand.bid)
+        .map(|(i, hand)| (i + 1) * bid(hand))
         .sum()
 }
 
+// Hands from weakest to strongest, each one classified once and sorted by its key
+pub fn rank_hands<'a>(input: &'a [Hand], rules: &Rules) -> Vec<&'a Hand> {
+    rank_by_key(input, |hand| rules.sort_key(&hand.cards))
+}
+
+// Hands from weakest to strongest, classifying both hands on every comparison
+pub fn rank_hands_by_comparison<'a>(input: &'a [Hand], rules: &Rules) -> Vec<&'a Hand> {
+    let mut sorted = input.iter().collect::<Vec<_>>();
+
+    sorted.sort_by(|a, b| rules.compare_hands(a, b));
+
+    sorted
+}
+
+// One hand's place in the final ranking
+#[derive(Debug, PartialEq)]
+pub struct Standing {
+    pub rank: usize,
+    pub cards: Vec<Card>,
+    pub hand_type: Type,
+    pub bid: usize,
+    pub winnings: usize,
+    // Where the tie-break against the hand ranked just below was decided, counting from 1 in the
+    // rules' tie-break order. `None` when the types already differ or the hands are identical.
+    pub tie_break: Option<usize>,
+}
+
+pub struct Standings {
+    // Weakest hand first
+    entries: Vec<Standing>,
+}
+
+impl Standings {
+    pub fn new(input: &[Hand], rules: &Rules) -> Self {
+        let ranked = rank_hands(input, rules);
+        let mut entries: Vec<Standing> = vec![];
+
+        for (i, hand) in ranked.iter().enumerate() {
+            let hand_type = rules.hand_type(&hand.cards);
+
+            let tie_break = match (i.checked_sub(1).map(|j| ranked[j]), entries.last()) {
+                (Some(below), Some(previous)) if previous.hand_type == hand_type => {
+                    let ranks = rules.tie_break_ranks(&hand.cards);
+                    let below_ranks = rules.tie_break_ranks(&below.cards);
+
+                    ranks
+                        .iter()
+                        .zip(below_ranks.iter())
+                        .position(|(a, b)| a != b)
+                        .map(|position| position + 1)
+                }
+                _ => None,
+            };
+
+            entries.push(Standing {
+                rank: i + 1,
+                cards: hand.cards.clone(),
+                hand_type,
+                bid: hand.bid,
+                winnings: (i + 1) * hand.bid,
+                tie_break,
+            });
+        }
+
+        Standings { entries }
+    }
+
+    pub fn entries(&self) -> &[Standing] {
+        &self.entries
+    }
+
+    pub fn total(&self) -> usize {
+        self.entries.iter().map(|entry| entry.winnings).sum()
+    }
+
+    pub fn to_table(&self) -> String {
+        let width = self
+            .entries
+            .iter()
+            .map(|entry| entry.cards.len())
+            .max()
+            .unwrap_or(0)
+            .max(4);
+
+        let mut lines = vec![format!(
+            "Rank | {:<width$} | Type            |  Bid | Winnings | Tie-break",
+            "Hand"
+        )];
+
+        for entry in self.entries.iter() {
+            let cards = entry
+                .cards
+                .iter()
+                .map(|card| card.to_string())
+                .collect::<String>();
+            let tie_break = entry
+                .tie_break
+                .map(|position| format!("card {}", position))
+                .unwrap_or_default();
+
+            let line = format!(
+                "{:>4} | {:<width$} | {:<15} | {:>4} | {:>8} | {}",
+                entry.rank,
+                cards,
+                entry.hand_type.to_string(),
+                entry.bid,
+                entry.winnings,
+                tie_break
+            );
+            lines.push(line.trim_end().to_string());
+        }
+
+        lines.join("\n")
+    }
+}
+
+// Each hand's bid multiplied by its rank when sorted weakest first
+pub fn solve_with_rules(input: &[Hand], rules: &Rules) -> usize {
+    for hand in input {
+        assert_eq!(hand.cards.len(), rules.hand_size, "Wrong number of cards");
+    }
+
+    winnings_by_key(input, |hand| rules.sort_key(&hand.cards), Hand::bid)
+}
+
+#[aoc(day7, part1)]
+pub fn solve_part1(input: &[Hand]) -> usize {
+    solve_with_rules(input, &Rules::standard())
+}
+
 #[aoc(day7, part2)]
 pub fn solve_part2(input: &[Hand]) -> usize {
-    1
+    solve_with_rules(input, &Rules::jokers())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::random::Rng;
 
     #[test]
     fn parse_cards_test() {
@@ -201,7 +548,7 @@ mod tests {
     fn parse_hand_test() {
         let input = "32T3K 765";
 
-        let result = parse_hand(input).ok().unwrap().1;
+        let result = parse_hand(1, input, &Rules::standard()).unwrap();
         assert_eq!(
             result.cards,
             vec![Card::Three, Card::Two, Card::T, Card::Three, Card::K]
@@ -209,6 +556,71 @@ mod tests {
         assert_eq!(result.bid, 765);
     }
 
+    #[test]
+    fn card_try_from_test() {
+        assert_eq!(Card::try_from('T'), Ok(Card::T));
+        assert_eq!(Card::try_from('t'), Err('t'));
+        assert_eq!(Card::try_from('1'), Err('1'));
+    }
+
+    #[test]
+    fn parse_hands_error_test() {
+        let rules = Rules::standard();
+        let error = |line, column, kind| Err(HandError { line, column, kind });
+
+        assert_eq!(
+            parse_hands("32T3K 765\nT55j5 684", &rules),
+            error(2, 4, HandErrorKind::BadCard('j'))
+        );
+        assert_eq!(
+            parse_hands("1KK77 28", &rules),
+            error(1, 1, HandErrorKind::BadCard('1'))
+        );
+        assert_eq!(
+            parse_hands("KTJJTT 220", &rules),
+            error(1, 6, HandErrorKind::WrongSize(6))
+        );
+        assert_eq!(
+            parse_hands("QQQ 483", &rules),
+            error(1, 4, HandErrorKind::WrongSize(3))
+        );
+        assert_eq!(
+            parse_hands("QQQJA", &rules),
+            error(1, 6, HandErrorKind::MissingBid)
+        );
+        assert_eq!(
+            parse_hands("QQQJA 48x", &rules),
+            error(1, 7, HandErrorKind::BadBid)
+        );
+        assert_eq!(
+            parse_hands("QQQJA  483", &rules),
+            error(1, 7, HandErrorKind::BadBid)
+        );
+    }
+
+    #[test]
+    fn parse_hands_rules_test() {
+        // A deck without aces, dealing hands of three
+        let rules = Rules {
+            ranking: CARDS[..12].to_vec(),
+            hand_size: 3,
+            ..Rules::standard()
+        };
+
+        assert_eq!(
+            parse_hands("QQ2 1\nK9T 2", &rules).map(|hands| hands.len()),
+            Ok(2)
+        );
+        assert_eq!(
+            parse_hands("QQ2 1\nKAT 2", &rules),
+            Err(HandError {
+                line: 2,
+                column: 2,
+                kind: HandErrorKind::BadCard('A')
+            })
+        );
+    }
+
     #[test]
     fn get_cards_type_pair_test() {
         let input = vec![Card::Three, Card::Two, Card::T, Card::Three, Card::K];
@@ -269,6 +681,274 @@ mod tests {
         assert_eq!(result, Type::FiveOfAKind);
     }
 
+    fn cards(input: &str) -> Vec<Card> {
+        parse_cards(input).ok().unwrap().1
+    }
+
+    #[test]
+    fn jokers_hand_type_test() {
+        let rules = Rules::jokers();
+
+        assert_eq!(rules.hand_type(&cards("32T3K")), Type::Pair);
+        assert_eq!(rules.hand_type(&cards("T55J5")), Type::FourOfAKind);
+        assert_eq!(rules.hand_type(&cards("KTJJT")), Type::FourOfAKind);
+        assert_eq!(rules.hand_type(&cards("QQQJA")), Type::FourOfAKind);
+        assert_eq!(rules.hand_type(&cards("2345J")), Type::Pair);
+        assert_eq!(rules.hand_type(&cards("2233J")), Type::FullHouse);
+        assert_eq!(rules.hand_type(&cards("JJJJJ")), Type::FiveOfAKind);
+        assert_eq!(rules.hand_type(&cards("JJJJ2")), Type::FiveOfAKind);
+    }
+
+    #[test]
+    fn compare_hands_test() {
+        let standard = Rules::standard();
+        let jokers = Rules::jokers();
+
+        let weak = Hand {
+            cards: cards("JKKK2"),
+            bid: 0,
+        };
+        let strong = Hand {
+            cards: cards("QQQQ2"),
+            bid: 0,
+        };
+
+        // Both are four of a kind with jokers, and the leading joker loses to the queen
+        assert_eq!(jokers.compare_hands(&weak, &strong), Ordering::Less);
+        assert_eq!(standard.compare_hands(&weak, &strong), Ordering::Less);
+
+        let joker = Hand {
+            cards: cards("J2345"),
+            bid: 0,
+        };
+        let two = Hand {
+            cards: cards("22345"),
+            bid: 0,
+        };
+        assert_eq!(jokers.compare_hands(&joker, &two), Ordering::Less);
+        assert_eq!(standard.compare_hands(&joker, &two), Ordering::Less);
+
+        // The jack beats the ten without jokers, but ranks below it as a joker
+        let jack = Hand {
+            cards: cards("KJ234"),
+            bid: 0,
+        };
+        let ten = Hand {
+            cards: cards("KT234"),
+            bid: 0,
+        };
+        let tens = Hand {
+            cards: cards("KT244"),
+            bid: 0,
+        };
+        assert_eq!(standard.compare_hands(&jack, &ten), Ordering::Greater);
+        assert_eq!(jokers.compare_hands(&jack, &tens), Ordering::Less);
+    }
+
+    #[test]
+    fn tie_break_test() {
+        let a = Hand {
+            cards: cards("A2345"),
+            bid: 0,
+        };
+        let b = Hand {
+            cards: cards("K6789"),
+            bid: 0,
+        };
+
+        let rules = |tie_break| Rules {
+            tie_break,
+            ..Rules::standard()
+        };
+
+        assert_eq!(
+            rules(TieBreak::InOrder).compare_hands(&a, &b),
+            Ordering::Greater
+        );
+        assert_eq!(
+            rules(TieBreak::Reversed).compare_hands(&a, &b),
+            Ordering::Less
+        );
+        assert_eq!(
+            rules(TieBreak::Strongest).compare_hands(&a, &b),
+            Ordering::Greater
+        );
+
+        // Ace low makes the king the strongest card of either hand
+        let ace_low = Rules {
+            ranking: [Card::A]
+                .into_iter()
+                .chain(CARDS[..12].iter().copied())
+                .collect(),
+            ..rules(TieBreak::Strongest)
+        };
+        assert_eq!(ace_low.compare_hands(&a, &b), Ordering::Less);
+    }
+
+    #[test]
+    fn hand_size_test() {
+        let rules = Rules {
+            hand_size: 7,
+            ..Rules::jokers()
+        };
+
+        assert_eq!(rules.hand_type(&cards("2233445")), Type::TwoPair);
+        assert_eq!(rules.hand_type(&cards("22334J5")), Type::FullHouse);
+        assert_eq!(rules.hand_type(&cards("2222JJ3")), Type::FiveOfAKind);
+        assert_eq!(Rules::standard().hand_type(&cards("234")), Type::HighCard);
+
+        let input = parse_hands("2233445 10\n2345678 20\n22334J5 30", &rules).unwrap();
+        assert_eq!(solve_with_rules(&input, &rules), 20 + 2 * 10 + 3 * 30);
+    }
+
+    #[test]
+    fn rules_side_by_side_test() {
+        let input = input_generator(EXAMPLE.trim());
+
+        let reversed_jokers = Rules {
+            tie_break: TieBreak::Reversed,
+            ..Rules::jokers()
+        };
+
+        assert_eq!(solve_with_rules(&input, &Rules::standard()), 6440);
+        assert_eq!(solve_with_rules(&input, &Rules::jokers()), 5905);
+        // The three hands with jokers are all four of a kind, and going by their last cards
+        // T55J5 is now the weakest of them
+        assert_eq!(
+            solve_with_rules(&input, &reversed_jokers),
+            765 + 2 * 28 + 3 * 684 + 4 * 220 + 5 * 483
+        );
+    }
+
+    fn random_hands(rng: &mut Rng, count: usize, size: usize) -> Vec<Hand> {
+        (0..count)
+            .map(|i| Hand {
+                // Few distinct cards, so there are plenty of groups and ties
+                cards: (0..size).map(|_| CARDS[rng.below(4) * 3]).collect(),
+                bid: i,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sort_key_matches_compare_hands_test() {
+        let mut rng = Rng::new(7);
+        let hands = random_hands(&mut rng, 200, 5);
+
+        let all_rules = [
+            Rules::standard(),
+            Rules::jokers(),
+            Rules {
+                tie_break: TieBreak::Reversed,
+                ..Rules::jokers()
+            },
+            Rules {
+                tie_break: TieBreak::Strongest,
+                ..Rules::standard()
+            },
+        ];
+
+        for rules in all_rules.iter() {
+            for a in hands.iter() {
+                for b in hands.iter() {
+                    assert_eq!(
+                        rules.sort_key(&a.cards).cmp(&rules.sort_key(&b.cards)),
+                        rules.compare_hands(a, b)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rank_hands_test() {
+        let mut rng = Rng::new(46);
+
+        for size in [3, 5, 7] {
+            let hands = random_hands(&mut rng, 500, size);
+
+            for rules in [Rules::standard(), Rules::jokers()] {
+                let bids = |ranked: Vec<&Hand>| ranked.iter().map(|h| h.bid).collect::<Vec<_>>();
+
+                // Both sorts are stable, so even identical hands come out in the same order
+                assert_eq!(
+                    bids(rank_hands(&hands, &rules)),
+                    bids(rank_hands_by_comparison(&hands, &rules))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn standings_test() {
+        let input = input_generator(EXAMPLE.trim());
+        let standings = Standings::new(&input, &Rules::standard());
+
+        assert_eq!(standings.total(), solve_part1(&input));
+        assert_eq!(
+            standings.entries()[2],
+            Standing {
+                rank: 3,
+                cards: cards("KK677"),
+                hand_type: Type::TwoPair,
+                bid: 28,
+                winnings: 84,
+                tie_break: Some(2),
+            }
+        );
+
+        let jokers = Standings::new(&input, &Rules::jokers());
+        assert_eq!(jokers.total(), solve_part2(&input));
+    }
+
+    #[test]
+    fn standings_table_test() {
+        let input = input_generator(EXAMPLE.trim());
+
+        assert_eq!(
+            Standings::new(&input, &Rules::standard()).to_table(),
+            r"
+Rank | Hand  | Type            |  Bid | Winnings | Tie-break
+   1 | 32T3K | One pair        |  765 |      765 |
+   2 | KTJJT | Two pair        |  220 |      440 |
+   3 | KK677 | Two pair        |   28 |       84 | card 2
+   4 | T55J5 | Three of a kind |  684 |     2736 |
+   5 | QQQJA | Three of a kind |  483 |     2415 | card 1
+"
+            .trim()
+        );
+
+        assert_eq!(
+            Standings::new(&input, &Rules::jokers()).to_table(),
+            r"
+Rank | Hand  | Type            |  Bid | Winnings | Tie-break
+   1 | 32T3K | One pair        |  765 |      765 |
+   2 | KK677 | Two pair        |   28 |       56 |
+   3 | T55J5 | Four of a kind  |  684 |     2052 |
+   4 | QQQJA | Four of a kind  |  483 |     1932 | card 1
+   5 | KTJJT | Four of a kind  |  220 |     1100 | card 1
+"
+            .trim()
+        );
+    }
+
+    #[test]
+    fn standings_identical_hands_test() {
+        let input = input_generator("AAAAK 1\nAAAAK 2");
+        let standings = Standings::new(&input, &Rules::standard());
+
+        assert_eq!(standings.entries()[1].tie_break, None);
+        assert_eq!(standings.total(), 1 + 2 * 2);
+    }
+
+    const EXAMPLE: &str = r"
+32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483
+";
+
     #[test]
     fn part1_test() {
         let input_str1: &str = r"
@@ -296,6 +976,6 @@ QQQJA 483
         .trim();
 
         let input = input_generator(input_str2);
-        assert_eq!(solve_part2(&input), 1);
+        assert_eq!(solve_part2(&input), 5905);
     }
 }
@@ -0,0 +1,264 @@
+// Classifies every possible hand under a set of rules and checks the number of hands of each type
+// against the count worked out combinatorially. For the standard 5-card rules that is all 13^5
+// hands.
+use super::{get_groups_type, Rules, Type};
+
+const TYPES: [Type; 7] = [
+    Type::HighCard,
+    Type::Pair,
+    Type::TwoPair,
+    Type::ThreeOfAKind,
+    Type::FullHouse,
+    Type::FourOfAKind,
+    Type::FiveOfAKind,
+];
+
+// Number of hands of each type, counting the same cards in a different order as a different hand
+#[derive(Debug, PartialEq)]
+pub struct Distribution {
+    // Indexed by `Type as usize`
+    counts: [usize; 7],
+}
+
+impl Distribution {
+    // Classifies every hand the rules can deal
+    pub fn enumerate(rules: &Rules) -> Self {
+        let mut counts = [0; 7];
+        let mut cards = vec![rules.ranking[0]; rules.hand_size];
+        // Index into the ranking of each card, the last card changing fastest
+        let mut indices = vec![0; rules.hand_size];
+
+        loop {
+            counts[rules.hand_type(&cards) as usize] += 1;
+
+            // Step to the next hand like an odometer, stopping once every position wraps
+            let Some(position) = indices.iter().rposition(|i| i + 1 < rules.ranking.len()) else {
+                break;
+            };
+
+            indices[position] += 1;
+            cards[position] = rules.ranking[indices[position]];
+
+            for i in (position + 1)..rules.hand_size {
+                indices[i] = 0;
+                cards[i] = rules.ranking[0];
+            }
+        }
+
+        Distribution { counts }
+    }
+
+    // Counts by grouping the hands on the sizes of their groups of matching cards. With `w`
+    // wildcards, the other `n = size - w` cards split into groups `g1 >= g2 >= ...` in
+    // `k! / (k - groups)! / (ways to swap equal sized groups)` ways from `k` ranks, and those cards
+    // can be laid out in `n! / (g1! g2! ...)` orders. The wildcards can sit in any
+    // `size choose w` positions and join the largest group.
+    pub fn combinatorial(rules: &Rules) -> Self {
+        let size = rules.hand_size;
+        let wildcard = rules.wildcard.filter(|card| rules.ranking.contains(card));
+        let ranks = rules.ranking.len() - wildcard.is_some() as usize;
+        let max_wildcards = if wildcard.is_some() { size } else { 0 };
+
+        let mut counts = [0; 7];
+
+        for wild in 0..=max_wildcards {
+            let others = size - wild;
+
+            for groups in partitions(others, others) {
+                if groups.len() > ranks {
+                    continue;
+                }
+
+                let mut equal_sizes = 1;
+                for run in groups.chunk_by(|a, b| a == b) {
+                    equal_sizes *= factorial(run.len());
+                }
+
+                let rank_choices = (0..groups.len()).map(|i| ranks - i).product::<usize>();
+                let orders =
+                    factorial(others) / groups.iter().map(|g| factorial(*g)).product::<usize>();
+
+                let mut sizes = groups.clone();
+                match sizes.first_mut() {
+                    Some(largest) => *largest += wild,
+                    None => sizes.push(wild),
+                }
+
+                counts[get_groups_type(&sizes) as usize] +=
+                    choose(size, wild) * rank_choices / equal_sizes * orders;
+            }
+        }
+
+        Distribution { counts }
+    }
+
+    pub fn count(&self, hand_type: Type) -> usize {
+        self.counts[hand_type as usize]
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+}
+
+// Ways of writing `n` as a sum of parts no bigger than `max`, largest part first
+fn partitions(n: usize, max: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![vec![]];
+    }
+
+    let mut result = vec![];
+
+    for first in (1..=max.min(n)).rev() {
+        for mut rest in partitions(n - first, first) {
+            rest.insert(0, first);
+            result.push(rest);
+        }
+    }
+
+    result
+}
+
+fn factorial(n: usize) -> usize {
+    (1..=n).product()
+}
+
+fn choose(n: usize, k: usize) -> usize {
+    factorial(n) / (factorial(k) * factorial(n - k))
+}
+
+// One column of counts for each named distribution, strongest type first
+pub fn table(columns: &[(&str, Distribution)]) -> String {
+    let header = columns
+        .iter()
+        .map(|(name, _)| format!("{:>8}", name))
+        .collect::<Vec<_>>()
+        .join(" | ");
+    let mut lines = vec![format!("{:<15} | {}", "Type", header)];
+
+    let row = |name: String, count: &dyn Fn(&Distribution) -> usize| {
+        let counts = columns
+            .iter()
+            .map(|(_, distribution)| format!("{:>8}", count(distribution)))
+            .collect::<Vec<_>>()
+            .join(" | ");
+
+        format!("{:<15} | {}", name, counts)
+    };
+
+    for hand_type in TYPES.iter().rev() {
+        lines.push(row(hand_type.to_string(), &|d| d.count(*hand_type)));
+    }
+    lines.push(row("Total".to_string(), &|d| d.total()));
+
+    lines.join("\n")
+}
+
+// Enumerates the hands under each named set of rules and compares them with the combinatorial
+// counts. Gives the table of counts when everything agrees.
+pub fn run(rule_sets: &[(&str, Rules)]) -> Result<String, String> {
+    let mut columns = vec![];
+
+    for (name, rules) in rule_sets.iter() {
+        let enumerated = Distribution::enumerate(rules);
+        let expected = Distribution::combinatorial(rules);
+
+        for hand_type in TYPES {
+            if enumerated.count(hand_type) != expected.count(hand_type) {
+                return Err(format!(
+                    "{} rules: {} hands of type {}, expected {}",
+                    name,
+                    enumerated.count(hand_type),
+                    hand_type,
+                    expected.count(hand_type)
+                ));
+            }
+        }
+
+        columns.push((*name, enumerated));
+    }
+
+    Ok(table(&columns))
+}
+
+// The puzzle's two sets of rules side by side
+pub fn run_standard_and_jokers() -> Result<String, String> {
+    run(&[("Standard", Rules::standard()), ("Jokers", Rules::jokers())])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day7::CARDS;
+
+    #[test]
+    fn standard_known_totals_test() {
+        let expected = Distribution::combinatorial(&Rules::standard());
+
+        assert_eq!(expected.count(Type::FiveOfAKind), 13);
+        assert_eq!(expected.count(Type::FourOfAKind), 780);
+        assert_eq!(expected.count(Type::FullHouse), 1560);
+        assert_eq!(expected.count(Type::ThreeOfAKind), 17160);
+        assert_eq!(expected.count(Type::TwoPair), 25740);
+        assert_eq!(expected.count(Type::Pair), 171600);
+        assert_eq!(expected.count(Type::HighCard), 154440);
+        assert_eq!(expected.total(), 13usize.pow(5));
+    }
+
+    #[test]
+    fn partitions_test() {
+        assert_eq!(
+            partitions(4, 4),
+            vec![
+                vec![4],
+                vec![3, 1],
+                vec![2, 2],
+                vec![2, 1, 1],
+                vec![1, 1, 1, 1]
+            ]
+        );
+        assert_eq!(partitions(0, 0), vec![Vec::<usize>::new()]);
+    }
+
+    #[test]
+    fn small_rules_test() {
+        // Three cards from a four card deck with a wildcard, small enough to count by hand. Three
+        // of a kind is 3 hands without the wildcard, 9 with one wildcard and a pair, 9 with two
+        // wildcards and the one with three. Only the 6 orders of the three other cards are high
+        // cards, and the remaining 36 hands are pairs.
+        let rules = Rules {
+            ranking: CARDS[..4].to_vec(),
+            wildcard: Some(CARDS[0]),
+            hand_size: 3,
+            ..Rules::standard()
+        };
+
+        let enumerated = Distribution::enumerate(&rules);
+        assert_eq!(enumerated, Distribution::combinatorial(&rules));
+        assert_eq!(enumerated.count(Type::ThreeOfAKind), 22);
+        assert_eq!(enumerated.count(Type::Pair), 36);
+        assert_eq!(enumerated.count(Type::HighCard), 6);
+        assert_eq!(enumerated.total(), 64);
+    }
+
+    #[test]
+    fn run_standard_and_jokers_test() {
+        let table = run_standard_and_jokers().unwrap();
+
+        assert_eq!(
+            table,
+            r"
+Type            | Standard |   Jokers
+Five of a kind  |       13 |      373
+Four of a kind  |      780 |     8580
+Full house      |     1560 |     3300
+Three of a kind |    17160 |    66000
+Two pair        |    25740 |    19800
+One pair        |   171600 |   178200
+High card       |   154440 |    95040
+Total           |   371293 |   371293
+"
+            .trim()
+        );
+    }
+}
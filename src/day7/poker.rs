@@ -0,0 +1,366 @@
+// Standard five-card poker with suited cards, such as `AsKhQdJcTs 100`. Hands use the same card
+// ranks and bids as Camel Cards, and are ranked into the same bid-and-rank total.
+use std::fmt;
+
+use super::{
+    column, group_sizes, parse_bid, rank_by_key, winnings_by_key, Card, HandError, HandErrorKind,
+};
+
+const HAND_SIZE: usize = 5;
+
+#[derive(PartialOrd, PartialEq, Debug, Copy, Clone, Eq, Ord)]
+pub enum Suit {
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades,
+}
+
+impl TryFrom<char> for Suit {
+    type Error = char;
+
+    fn try_from(value: char) -> Result<Self, char> {
+        match value {
+            'c' => Ok(Suit::Clubs),
+            'd' => Ok(Suit::Diamonds),
+            'h' => Ok(Suit::Hearts),
+            's' => Ok(Suit::Spades),
+            _ => Err(value),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Copy, Clone, Eq)]
+pub struct SuitedCard {
+    pub card: Card,
+    pub suit: Suit,
+}
+
+impl fmt::Display for SuitedCard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let suit = match self.suit {
+            Suit::Clubs => 'c',
+            Suit::Diamonds => 'd',
+            Suit::Hearts => 'h',
+            Suit::Spades => 's',
+        };
+
+        write!(f, "{}{}", self.card, suit)
+    }
+}
+
+#[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Copy, Clone)]
+pub enum PokerType {
+    HighCard,
+    Pair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct PokerHand {
+    cards: Vec<SuitedCard>,
+    bid: usize,
+}
+
+impl PokerHand {
+    pub fn cards(&self) -> &[SuitedCard] {
+        &self.cards
+    }
+
+    pub fn bid(&self) -> usize {
+        self.bid
+    }
+
+    // Highest to lowest
+    fn ranks(&self) -> Vec<Card> {
+        let mut ranks = self.cards.iter().map(|card| card.card).collect::<Vec<_>>();
+        ranks.sort_by(|a, b| b.cmp(a));
+
+        ranks
+    }
+
+    fn is_flush(&self) -> bool {
+        self.cards
+            .iter()
+            .all(|card| card.suit == self.cards[0].suit)
+    }
+
+    // Top card of a straight, which is the five for the ace-low wheel
+    fn straight_high(&self) -> Option<Card> {
+        let ranks = self.ranks();
+
+        if ranks == [Card::A, Card::Five, Card::Four, Card::Three, Card::Two] {
+            return Some(Card::Five);
+        }
+
+        let consecutive = ranks
+            .windows(2)
+            .all(|pair| pair[0] as usize == pair[1] as usize + 1);
+        consecutive.then_some(ranks[0])
+    }
+
+    pub fn poker_type(&self) -> PokerType {
+        let ranks = self.ranks();
+        let groups = group_sizes(&ranks, None);
+
+        match (self.straight_high(), self.is_flush(), groups.as_slice()) {
+            (Some(_), true, _) => PokerType::StraightFlush,
+            (_, _, [4, ..]) => PokerType::FourOfAKind,
+            (_, _, [3, 2]) => PokerType::FullHouse,
+            (_, true, _) => PokerType::Flush,
+            (Some(_), _, _) => PokerType::Straight,
+            (_, _, [3, ..]) => PokerType::ThreeOfAKind,
+            (_, _, [2, 2, ..]) => PokerType::TwoPair,
+            (_, _, [2, ..]) => PokerType::Pair,
+            _ => PokerType::HighCard,
+        }
+    }
+
+    // Ranks in the order they're compared when two hands have the same type: the bigger groups
+    // first and then the higher ranks, so pairs come before their kickers. Straights only compare
+    // their top card.
+    fn kickers(&self) -> Vec<Card> {
+        if let Some(high) = self.straight_high() {
+            return vec![high];
+        }
+
+        let ranks = self.ranks();
+        let mut groups = ranks
+            .chunk_by(|a, b| a == b)
+            .map(|group| (group.len(), group[0]))
+            .collect::<Vec<_>>();
+        groups.sort_by(|a, b| b.cmp(a));
+
+        groups.into_iter().map(|(_, rank)| rank).collect()
+    }
+
+    // The type in the top bits, then 4 bits for each kicker. Hands that differ only in their
+    // suits have the same key and split the pot in real poker.
+    pub fn sort_key(&self) -> u64 {
+        let kickers = self.kickers();
+
+        (0..HAND_SIZE).fold(self.poker_type() as u64, |key, i| {
+            let rank = kickers.get(i).map_or(0, |card| *card as u64 + 1);
+            (key << 4) | rank
+        })
+    }
+}
+
+// Five cards, each a rank followed by a suit, then the bid
+pub fn parse_hand(line: usize, input: &str) -> Result<PokerHand, HandError> {
+    let error = |rest, kind| HandError {
+        line,
+        column: column(input, rest),
+        kind,
+    };
+
+    let mut cards: Vec<SuitedCard> = vec![];
+    // Where each card starts, to point at the first one too many
+    let mut starts = vec![];
+    let mut rest = input;
+
+    while let Some(c) = rest.chars().next().filter(|c| *c != ' ') {
+        let card = Card::try_from(c).map_err(|c| error(rest, HandErrorKind::BadCard(c)))?;
+
+        let after = &rest[c.len_utf8()..];
+        let s = after.chars().next().unwrap_or(' ');
+        let suit = Suit::try_from(s).map_err(|s| error(after, HandErrorKind::BadSuit(s)))?;
+
+        let suited = SuitedCard { card, suit };
+        if cards.contains(&suited) {
+            return Err(error(rest, HandErrorKind::DuplicateCard));
+        }
+
+        starts.push(rest);
+        cards.push(suited);
+        rest = &after[s.len_utf8()..];
+    }
+
+    if cards.len() != HAND_SIZE {
+        let at = starts.get(HAND_SIZE).copied().unwrap_or(rest);
+        return Err(error(at, HandErrorKind::WrongSize(cards.len())));
+    }
+
+    let bid = parse_bid(line, input, rest)?;
+
+    Ok(PokerHand { cards, bid })
+}
+
+pub fn parse_hands(input: &str) -> Result<Vec<PokerHand>, HandError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| parse_hand(i + 1, line))
+        .collect()
+}
+
+// Hands from weakest to strongest
+pub fn rank_hands(input: &[PokerHand]) -> Vec<&PokerHand> {
+    rank_by_key(input, PokerHand::sort_key)
+}
+
+// Each hand's bid multiplied by its rank, as in Camel Cards. Tied hands keep their input order.
+pub fn total_winnings(input: &[PokerHand]) -> usize {
+    winnings_by_key(input, PokerHand::sort_key, PokerHand::bid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day7::{get_cards_type, Type};
+
+    fn hand(cards: &str) -> PokerHand {
+        parse_hand(1, &format!("{} 1", cards)).unwrap()
+    }
+
+    #[test]
+    fn parse_hand_test() {
+        let result = parse_hand(1, "AsKhQdJcTs 10").unwrap();
+
+        assert_eq!(
+            result.cards()[..2],
+            [
+                SuitedCard {
+                    card: Card::A,
+                    suit: Suit::Spades
+                },
+                SuitedCard {
+                    card: Card::K,
+                    suit: Suit::Hearts
+                }
+            ]
+        );
+        assert_eq!(result.bid(), 10);
+
+        let text = result
+            .cards()
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<String>();
+        assert_eq!(text, "AsKhQdJcTs");
+    }
+
+    #[test]
+    fn parse_hands_error_test() {
+        let error = |line, column, kind| Err(HandError { line, column, kind });
+
+        assert_eq!(
+            parse_hands("AsKhQdJcTs 1\nAsKhQxJcTs 2"),
+            error(2, 6, HandErrorKind::BadSuit('x'))
+        );
+        assert_eq!(
+            parse_hands("AsKhQdJc1s 1"),
+            error(1, 9, HandErrorKind::BadCard('1'))
+        );
+        assert_eq!(
+            parse_hands("AsKhQdJcAs 1"),
+            error(1, 9, HandErrorKind::DuplicateCard)
+        );
+        assert_eq!(
+            parse_hands("AsKhQdJcTs9s 1"),
+            error(1, 11, HandErrorKind::WrongSize(6))
+        );
+        assert_eq!(
+            parse_hands("AsKhQdJc 1"),
+            error(1, 9, HandErrorKind::WrongSize(4))
+        );
+        assert_eq!(
+            parse_hands("AsKhQdJcT 1"),
+            error(1, 10, HandErrorKind::BadSuit(' '))
+        );
+        assert_eq!(
+            parse_hands("AsKhQdJcTs"),
+            error(1, 11, HandErrorKind::MissingBid)
+        );
+    }
+
+    #[test]
+    fn poker_type_test() {
+        assert_eq!(hand("AsKsQsJsTs").poker_type(), PokerType::StraightFlush);
+        assert_eq!(hand("5d4d3d2dAd").poker_type(), PokerType::StraightFlush);
+        assert_eq!(hand("AhAdAcAs2h").poker_type(), PokerType::FourOfAKind);
+        assert_eq!(hand("KhKdKc2s2h").poker_type(), PokerType::FullHouse);
+        assert_eq!(hand("2h7h9hJhKh").poker_type(), PokerType::Flush);
+        assert_eq!(hand("9h8d7c6s5h").poker_type(), PokerType::Straight);
+        assert_eq!(hand("5h4d3c2sAh").poker_type(), PokerType::Straight);
+        assert_eq!(hand("QhQdQc2s3h").poker_type(), PokerType::ThreeOfAKind);
+        assert_eq!(hand("QhQd2c2s3h").poker_type(), PokerType::TwoPair);
+        assert_eq!(hand("QhQd4c2s3h").poker_type(), PokerType::Pair);
+        assert_eq!(hand("Qh9d4c2s3h").poker_type(), PokerType::HighCard);
+
+        // Wrapping round from the king isn't a straight
+        assert_eq!(hand("3h2dAcKsQh").poker_type(), PokerType::HighCard);
+    }
+
+    #[test]
+    fn camel_cards_types_differ_test() {
+        let royal = hand("AhKhQhJhTh");
+        let ranks = royal.cards().iter().map(|c| c.card).collect::<Vec<_>>();
+
+        assert_eq!(get_cards_type(&ranks), Type::HighCard);
+        assert_eq!(royal.poker_type(), PokerType::StraightFlush);
+    }
+
+    #[test]
+    fn straights_test() {
+        let wheel = hand("5h4d3c2sAh");
+        let six_high = hand("6h5d4c3s2h");
+        let ace_high = hand("AhKdQcJsTh");
+        let king_high = hand("KhQdJcTs9h");
+
+        assert!(wheel.sort_key() < six_high.sort_key());
+        assert!(king_high.sort_key() < ace_high.sort_key());
+        assert!(six_high.sort_key() < king_high.sort_key());
+        // The ace in the wheel doesn't make it beat an ace-high straight
+        assert!(wheel.sort_key() < ace_high.sort_key());
+    }
+
+    #[test]
+    fn kickers_test() {
+        // Same pair of aces, the king kicker wins
+        assert!(hand("AsAcQd4c3d").sort_key() < hand("AhAdKc4s3h").sort_key());
+        // Same two pairs, decided by the last card
+        assert!(hand("KhKd9c9s2h").sort_key() < hand("KsKc9d9h3d").sort_key());
+        // The pairs come before the higher single card
+        assert!(hand("AhQdQc3s3h").sort_key() < hand("2hKdKc3s3d").sort_key());
+        // Full houses go by the three of a kind first
+        assert!(hand("2h2d2cAsAh").sort_key() < hand("3h3d3c2s2h").sort_key());
+        // Flushes compare card by card from the top
+        assert!(hand("Ad9d7d5d2d").sort_key() < hand("Ah9h7h5h3h").sort_key());
+
+        // Only the suits differ, so it's a tie
+        assert_eq!(hand("AhKdQc9s2h").sort_key(), hand("AsKcQd9h2c").sort_key());
+    }
+
+    #[test]
+    fn total_winnings_test() {
+        let input = parse_hands(
+            r"
+5h4d3c2sAh 10
+AhAdKc4s3h 20
+2h7h9hJhKh 30
+KhKdKc2s2h 40
+9h8d7c6s5h 50
+"
+            .trim(),
+        )
+        .unwrap();
+
+        // Pair, wheel, nine-high straight, flush, full house
+        let bids = rank_hands(&input)
+            .iter()
+            .map(|h| h.bid())
+            .collect::<Vec<_>>();
+        assert_eq!(bids, vec![20, 10, 50, 30, 40]);
+        assert_eq!(
+            total_winnings(&input),
+            20 + 2 * 10 + 3 * 50 + 4 * 30 + 5 * 40
+        );
+    }
+}
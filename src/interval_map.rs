@@ -0,0 +1,448 @@
+use std::fmt;
+use std::ops::Range;
+
+use crate::range_set::RangeSet;
+
+// Values in `src..src + len` move to `dest..dest + len`
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Entry {
+    dest: usize,
+    src: usize,
+    len: usize,
+}
+
+impl Entry {
+    fn end(&self) -> usize {
+        self.src + self.len
+    }
+}
+
+// A run of source values that are all shifted by the same amount. Identity segments cover the
+// gaps between entries.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Segment {
+    pub source: Range<usize>,
+    pub dest: usize,
+    pub identity: bool,
+}
+
+impl Segment {
+    pub fn map(&self, value: usize) -> usize {
+        value - self.source.start + self.dest
+    }
+
+    pub fn dest_range(&self) -> Range<usize> {
+        self.dest..(self.dest + self.source.len())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum IntervalMapError {
+    // A `(dest, src, len)` triple whose source or destination range runs past `usize::MAX`
+    Overflow(usize, usize, usize),
+    // Two triples whose source ranges share values, in source order
+    Overlap((usize, usize, usize), (usize, usize, usize)),
+}
+
+// Piecewise offset map over `usize`, built from `(dest, src, len)` triples with non-overlapping
+// sources. Values that aren't covered by any entry map to themselves.
+//
+// Every entry's ranges fit in `usize`, so none of the arithmetic on mapped values can overflow.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntervalMap {
+    // Sorted by source start
+    entries: Vec<Entry>,
+}
+
+impl IntervalMap {
+    pub fn try_from_triples<I: IntoIterator<Item = (usize, usize, usize)>>(
+        triples: I,
+    ) -> Result<Self, IntervalMapError> {
+        let mut entries: Vec<Entry> = vec![];
+
+        for (dest, src, len) in triples {
+            if src.checked_add(len).is_none() || dest.checked_add(len).is_none() {
+                return Err(IntervalMapError::Overflow(dest, src, len));
+            }
+
+            if len > 0 {
+                entries.push(Entry { dest, src, len });
+            }
+        }
+
+        entries.sort_by_key(|entry| entry.src);
+
+        if let Some(pair) = entries.windows(2).find(|pair| pair[0].end() > pair[1].src) {
+            let triple = |entry: &Entry| (entry.dest, entry.src, entry.len);
+            return Err(IntervalMapError::Overlap(
+                triple(&pair[0]),
+                triple(&pair[1]),
+            ));
+        }
+
+        Ok(IntervalMap { entries })
+    }
+
+    // Panics if an entry overflows or two entries overlap, see `try_from_triples`
+    pub fn from_triples<I: IntoIterator<Item = (usize, usize, usize)>>(triples: I) -> Self {
+        Self::try_from_triples(triples).expect("Bad map entries")
+    }
+
+    pub fn get(&self, value: usize) -> usize {
+        let index = self.entries.partition_point(|entry| entry.src <= value);
+
+        match index.checked_sub(1).map(|i| &self.entries[i]) {
+            Some(entry) if value < entry.end() => value - entry.src + entry.dest,
+            _ => value,
+        }
+    }
+
+    // Images of the parts of `range` that fall in each segment, in source order
+    pub fn map_range(&self, range: Range<usize>) -> Vec<Range<usize>> {
+        let mut mapped = vec![];
+
+        for segment in self.segments_from(range.start) {
+            if segment.source.start >= range.end {
+                break;
+            }
+
+            let start = segment.source.start.max(range.start);
+            let end = segment.source.end.min(range.end);
+
+            let dest = segment.map(start);
+            mapped.push(dest..(dest + (end - start)));
+        }
+
+        mapped
+    }
+
+    // Image of every value in the set
+    pub fn map_set(&self, set: &RangeSet) -> RangeSet {
+        set.ranges()
+            .iter()
+            .flat_map(|range| self.map_range(range.clone()))
+            .collect()
+    }
+
+    // Every source value that maps into `range`. Gaps map to themselves, so a value can have
+    // more than one preimage.
+    pub fn preimage(&self, range: Range<usize>) -> RangeSet {
+        self.preimage_pieces(range).into_iter().collect()
+    }
+
+    pub fn preimage_set(&self, set: &RangeSet) -> RangeSet {
+        set.ranges()
+            .iter()
+            .flat_map(|range| self.preimage_pieces(range.clone()))
+            .collect()
+    }
+
+    fn preimage_pieces(&self, range: Range<usize>) -> Vec<Range<usize>> {
+        let mut sources = vec![];
+
+        for segment in self.segments() {
+            let dest = segment.dest_range();
+
+            let start = dest.start.max(range.start);
+            let end = dest.end.min(range.end);
+
+            if start < end {
+                let src = segment.source.start + (start - dest.start);
+                sources.push(src..(src + (end - start)));
+            }
+        }
+
+        sources
+    }
+
+    // Map equivalent to applying `self` and then `next`
+    pub fn compose(&self, next: &IntervalMap) -> IntervalMap {
+        let mut entries: Vec<Entry> = vec![];
+
+        for segment in self.segments() {
+            let mut src = segment.source.start;
+
+            for image in next.map_range(segment.dest_range()) {
+                let len = image.len();
+
+                if image.start != src {
+                    match entries.last_mut() {
+                        // Extend the previous entry when this piece continues it with the same shift
+                        Some(last) if last.end() == src && last.dest + last.len == image.start => {
+                            last.len += len;
+                        }
+                        _ => entries.push(Entry {
+                            dest: image.start,
+                            src,
+                            len,
+                        }),
+                    }
+                }
+
+                src += len;
+            }
+        }
+
+        IntervalMap { entries }
+    }
+
+    // Every segment in order, gaps included, covering `0..usize::MAX`
+    pub fn segments(&self) -> Segments<'_> {
+        self.segments_from(0)
+    }
+
+    // Segments from the one containing `value` onwards, the first one starting at `value`
+    fn segments_from(&self, value: usize) -> Segments<'_> {
+        Segments {
+            entries: &self.entries,
+            index: self.entries.partition_point(|entry| entry.end() <= value),
+            next: value,
+        }
+    }
+}
+
+// One `dest src len` line per entry, the same format as the almanac maps
+impl fmt::Display for IntervalMap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let lines = self
+            .entries
+            .iter()
+            .map(|entry| format!("{} {} {}", entry.dest, entry.src, entry.len))
+            .collect::<Vec<_>>();
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+pub struct Segments<'a> {
+    entries: &'a [Entry],
+    index: usize,
+    // Start of the next segment
+    next: usize,
+}
+
+impl Iterator for Segments<'_> {
+    type Item = Segment;
+
+    fn next(&mut self) -> Option<Segment> {
+        if self.next == usize::MAX {
+            return None;
+        }
+
+        let start = self.next;
+
+        let segment = match self.entries.get(self.index) {
+            Some(entry) if start < entry.src => Segment {
+                source: start..entry.src,
+                dest: start,
+                identity: true,
+            },
+            Some(entry) => {
+                self.index += 1;
+
+                Segment {
+                    source: start..entry.end(),
+                    dest: start - entry.src + entry.dest,
+                    identity: false,
+                }
+            }
+            None => Segment {
+                source: start..usize::MAX,
+                dest: start,
+                identity: true,
+            },
+        };
+
+        self.next = segment.source.end;
+        Some(segment)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+
+    fn example() -> IntervalMap {
+        IntervalMap::from_triples(vec![(50, 98, 2), (52, 50, 48)])
+    }
+
+    #[test]
+    fn try_from_triples_overflow_test() {
+        let result = IntervalMap::try_from_triples(vec![(0, 10, 5), (0, usize::MAX - 1, 2)]);
+        assert_eq!(
+            result,
+            Err(IntervalMapError::Overflow(0, usize::MAX - 1, 2))
+        );
+
+        let result = IntervalMap::try_from_triples(vec![(usize::MAX, 0, 1)]);
+        assert_eq!(result, Err(IntervalMapError::Overflow(usize::MAX, 0, 1)));
+
+        // Ending exactly at `usize::MAX` fits
+        let map = IntervalMap::try_from_triples(vec![(0, usize::MAX - 2, 2)]).unwrap();
+        assert_eq!(map.get(usize::MAX - 1), 1);
+        assert_eq!(map.get(usize::MAX), usize::MAX);
+    }
+
+    #[test]
+    fn try_from_triples_overlap_test() {
+        let result = IntervalMap::try_from_triples(vec![(200, 2, 3), (100, 0, 10)]);
+        assert_eq!(
+            result,
+            Err(IntervalMapError::Overlap((100, 0, 10), (200, 2, 3)))
+        );
+
+        // Sharing an end point isn't an overlap, and empty entries are dropped first
+        let map = IntervalMap::try_from_triples(vec![(100, 0, 10), (200, 10, 5), (0, 5, 0)]);
+        assert_eq!(map.unwrap().get(10), 200);
+    }
+
+    #[test]
+    fn get_test() {
+        let map = example();
+
+        assert_eq!(map.get(0), 0);
+        assert_eq!(map.get(49), 49);
+        assert_eq!(map.get(50), 52);
+        assert_eq!(map.get(79), 81);
+        assert_eq!(map.get(97), 99);
+        assert_eq!(map.get(98), 50);
+        assert_eq!(map.get(99), 51);
+        assert_eq!(map.get(100), 100);
+        assert_eq!(map.get(usize::MAX), usize::MAX);
+    }
+
+    #[test]
+    fn map_range_test() {
+        let map = example();
+
+        assert_eq!(map.map_range(79..93), vec![81..95]);
+        assert_eq!(
+            map.map_range(40..110),
+            vec![40..50, 52..100, 50..52, 100..110]
+        );
+        assert_eq!(map.map_range(99..99), vec![]);
+    }
+
+    #[test]
+    fn preimage_test() {
+        let map = example();
+
+        assert_eq!(map.preimage(50..52).ranges(), &[98..100]);
+        assert_eq!(map.preimage(81..95).ranges(), &[79..93]);
+        assert_eq!(map.preimage(99..101).ranges(), &[97..98, 100..101]);
+        assert!(map.preimage(10..10).is_empty());
+
+        // 10..15 is reached from both the entry and the gap, and nothing reaches 0..5
+        let map = IntervalMap::from_triples(vec![(10, 0, 5)]);
+        assert_eq!(map.preimage(8..15).ranges(), &[0..5, 8..15]);
+        assert!(map.preimage(0..5).is_empty());
+    }
+
+    #[test]
+    fn preimage_brute_force_test() {
+        let map = IntervalMap::from_triples(vec![(0, 15, 37), (37, 52, 2), (39, 0, 15)]);
+
+        for start in 0..60 {
+            let range = start..(start + 7);
+            let preimage = map.preimage(range.clone());
+
+            for value in 0..120 {
+                assert_eq!(preimage.contains(value), range.contains(&map.get(value)));
+            }
+        }
+    }
+
+    #[test]
+    fn map_set_test() {
+        let map = example();
+        let set = vec![40..60, 95..110].into_iter().collect::<RangeSet>();
+
+        // 98..100 lands on 50..52, right next to the gap's 40..50 and the 52..62 image
+        assert_eq!(map.map_set(&set).ranges(), &[40..62, 97..110]);
+        assert_eq!(map.preimage_set(&map.map_set(&set)).intersection(&set), set);
+    }
+
+    #[test]
+    fn compose_test() {
+        let first = example();
+        let second = IntervalMap::from_triples(vec![(0, 15, 37), (37, 52, 2), (39, 0, 15)]);
+
+        let composed = first.compose(&second);
+
+        for value in 0..200 {
+            assert_eq!(composed.get(value), second.get(first.get(value)));
+        }
+    }
+
+    #[test]
+    fn compose_merges_entries_test() {
+        let first = IntervalMap::from_triples(vec![(100, 0, 10), (110, 10, 10)]);
+        let second = IntervalMap::default();
+
+        let composed = first.compose(&second);
+        assert_eq!(composed, IntervalMap::from_triples(vec![(100, 0, 20)]));
+    }
+
+    #[test]
+    fn compose_drops_identity_test() {
+        let first = IntervalMap::from_triples(vec![(10, 0, 5)]);
+        let second = IntervalMap::from_triples(vec![(0, 10, 5)]);
+
+        // 0..5 goes to 10..15 and back again, only 10..15 is left moved
+        assert_eq!(
+            first.compose(&second),
+            IntervalMap::from_triples(vec![(0, 10, 5)])
+        );
+    }
+
+    #[test]
+    fn display_test() {
+        let map = example();
+
+        assert_eq!(map.to_string(), "52 50 48\n50 98 2");
+    }
+
+    #[test]
+    fn segments_test() {
+        let map = example();
+
+        let segments = map.segments().collect::<Vec<_>>();
+        assert_eq!(
+            segments,
+            vec![
+                Segment {
+                    source: 0..50,
+                    dest: 0,
+                    identity: true,
+                },
+                Segment {
+                    source: 50..98,
+                    dest: 52,
+                    identity: false,
+                },
+                Segment {
+                    source: 98..100,
+                    dest: 50,
+                    identity: false,
+                },
+                Segment {
+                    source: 100..usize::MAX,
+                    dest: 100,
+                    identity: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn segments_from_test() {
+        let map = example();
+
+        let starts = map
+            .segments_from(60)
+            .map(|segment| segment.source.start)
+            .collect::<Vec<_>>();
+        assert_eq!(starts, vec![60, 98, 100]);
+    }
+}
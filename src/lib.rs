@@ -5,10 +5,14 @@ extern crate aoc_runner_derive;
 
 pub mod day1;
 pub mod day2;
+pub mod day3;
 pub mod day4;
 pub mod day5;
 pub mod day6;
 pub mod day7;
+pub mod interval_map;
 pub mod parsers;
+pub mod random;
+pub mod range_set;
 
 aoc_lib! { year = 2023 }
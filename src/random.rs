@@ -0,0 +1,69 @@
+// Small deterministic generator for simulations and synthetic test data.
+// Based on SplitMix64, which is plenty for generating puzzle inputs.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Uniform value in `0..bound`, bound must be non-zero
+    pub fn below(&mut self, bound: usize) -> usize {
+        assert!(bound > 0, "Bound must be non-zero");
+
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    // Uniform value in `low..=high`, low must not be above high
+    pub fn between(&mut self, low: usize, high: usize) -> usize {
+        assert!(low <= high, "Low {} must not be above high {}", low, high);
+
+        low + self.below(high - low + 1)
+    }
+
+    pub fn chance(&mut self, numerator: usize, denominator: usize) -> bool {
+        self.below(denominator) < numerator
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_sequence_test() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+    }
+
+    #[test]
+    fn between_test() {
+        let mut rng = Rng::new(7);
+
+        for _ in 0..1000 {
+            let value = rng.between(3, 6);
+            assert!((3..=6).contains(&value));
+        }
+
+        assert_eq!(rng.between(5, 5), 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "Low 1 must not be above high 0")]
+    fn between_empty_range_test() {
+        Rng::new(7).between(1, 0);
+    }
+}
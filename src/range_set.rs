@@ -0,0 +1,240 @@
+use std::ops::Range;
+
+// Set of `usize` values kept as sorted, disjoint half-open ranges. Ranges that touch are merged,
+// so two sets with the same values always have the same ranges.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RangeSet {
+    ranges: Vec<Range<usize>>,
+}
+
+impl RangeSet {
+    pub fn new() -> Self {
+        RangeSet::default()
+    }
+
+    pub fn ranges(&self) -> &[Range<usize>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    // Number of values in the set
+    pub fn len(&self) -> usize {
+        self.ranges.iter().map(|range| range.len()).sum()
+    }
+
+    pub fn min(&self) -> Option<usize> {
+        self.ranges.first().map(|range| range.start)
+    }
+
+    // Largest value in the set, not the exclusive end of the last range
+    pub fn max(&self) -> Option<usize> {
+        self.ranges.last().map(|range| range.end - 1)
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        let index = self.ranges.partition_point(|range| range.end <= value);
+
+        self.ranges
+            .get(index)
+            .is_some_and(|range| range.contains(&value))
+    }
+
+    pub fn insert(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+
+        // Every range from `first` up to `last` overlaps or touches the new one
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+
+        let mut merged = range;
+        if first < last {
+            merged.start = merged.start.min(self.ranges[first].start);
+            merged.end = merged.end.max(self.ranges[last - 1].end);
+        }
+
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    pub fn union(&self, other: &RangeSet) -> RangeSet {
+        let mut union = self.clone();
+
+        for range in other.ranges.iter() {
+            union.insert(range.clone());
+        }
+
+        union
+    }
+
+    pub fn intersection(&self, other: &RangeSet) -> RangeSet {
+        let mut ranges = vec![];
+        let (mut i, mut j) = (0, 0);
+
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+            if start < end {
+                ranges.push(start..end);
+            }
+
+            // Move past whichever range finishes first
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        RangeSet { ranges }
+    }
+
+    pub fn difference(&self, other: &RangeSet) -> RangeSet {
+        let mut ranges = vec![];
+        let mut j = 0;
+
+        for range in self.ranges.iter() {
+            let mut start = range.start;
+
+            // Skip the ranges of `other` that finish before this one starts
+            while j < other.ranges.len() && other.ranges[j].end <= start {
+                j += 1;
+            }
+
+            let mut k = j;
+            while k < other.ranges.len() && other.ranges[k].start < range.end {
+                let removed = &other.ranges[k];
+
+                if removed.start > start {
+                    ranges.push(start..removed.start);
+                }
+                start = start.max(removed.end);
+                k += 1;
+            }
+
+            if start < range.end {
+                ranges.push(start..range.end);
+            }
+        }
+
+        RangeSet { ranges }
+    }
+}
+
+impl FromIterator<Range<usize>> for RangeSet {
+    fn from_iter<I: IntoIterator<Item = Range<usize>>>(iter: I) -> Self {
+        let mut ranges = iter
+            .into_iter()
+            .filter(|range| !range.is_empty())
+            .collect::<Vec<_>>();
+        ranges.sort_by_key(|range| range.start);
+
+        let mut merged: Vec<Range<usize>> = vec![];
+
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+
+        RangeSet { ranges: merged }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: Vec<Range<usize>>) -> RangeSet {
+        ranges.into_iter().collect()
+    }
+
+    #[test]
+    fn from_iter_coalesces_test() {
+        let result = set(vec![10..20, 0..5, 5..7, 15..25, 30..30, 26..28]);
+
+        assert_eq!(result.ranges(), &[0..7, 10..25, 26..28]);
+        assert_eq!(result.len(), 24);
+        assert_eq!(result.min(), Some(0));
+        assert_eq!(result.max(), Some(27));
+    }
+
+    #[test]
+    fn insert_test() {
+        let mut result = set(vec![0..5, 10..15, 20..25]);
+
+        result.insert(5..10);
+        assert_eq!(result.ranges(), &[0..15, 20..25]);
+
+        result.insert(30..35);
+        assert_eq!(result.ranges(), &[0..15, 20..25, 30..35]);
+
+        result.insert(17..18);
+        assert_eq!(result.ranges(), &[0..15, 17..18, 20..25, 30..35]);
+
+        result.insert(12..31);
+        assert_eq!(result.ranges(), &[0..35]);
+
+        result.insert(40..40);
+        assert_eq!(result.ranges(), &[0..35]);
+    }
+
+    #[test]
+    fn contains_test() {
+        let result = set(vec![0..5, 10..15]);
+
+        assert!(result.contains(0));
+        assert!(result.contains(4));
+        assert!(!result.contains(5));
+        assert!(result.contains(10));
+        assert!(!result.contains(15));
+    }
+
+    #[test]
+    fn union_test() {
+        let a = set(vec![0..5, 10..15]);
+        let b = set(vec![5..8, 14..20, 30..31]);
+
+        assert_eq!(a.union(&b).ranges(), &[0..8, 10..20, 30..31]);
+    }
+
+    #[test]
+    fn intersection_test() {
+        let a = set(vec![0..10, 20..30]);
+        let b = set(vec![5..25, 28..40]);
+
+        assert_eq!(a.intersection(&b).ranges(), &[5..10, 20..25, 28..30]);
+        assert!(a.intersection(&RangeSet::new()).is_empty());
+    }
+
+    #[test]
+    fn difference_test() {
+        let a = set(vec![0..10, 20..30]);
+        let b = set(vec![2..4, 6..22, 25..26, 29..50]);
+
+        assert_eq!(a.difference(&b).ranges(), &[0..2, 4..6, 22..25, 26..29]);
+        assert_eq!(b.difference(&a).ranges(), &[10..20, 30..50]);
+        assert_eq!(a.difference(&RangeSet::new()), a);
+    }
+
+    #[test]
+    fn set_operations_brute_force_test() {
+        let a = set(vec![0..3, 5..9, 12..13, 20..26]);
+        let b = set(vec![2..6, 8..12, 13..15, 19..21, 25..30]);
+
+        for value in 0..35 {
+            let (in_a, in_b) = (a.contains(value), b.contains(value));
+
+            assert_eq!(a.union(&b).contains(value), in_a || in_b);
+            assert_eq!(a.intersection(&b).contains(value), in_a && in_b);
+            assert_eq!(a.difference(&b).contains(value), in_a && !in_b);
+        }
+    }
+}